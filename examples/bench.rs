@@ -0,0 +1,28 @@
+//! Search the bench suite at a fixed depth on one thread and print the total node count
+//! (the search signature) and speed. Compare the node count between builds to tell
+//! functional changes from pure speed-ups.
+//!
+//! ```sh
+//! cargo run --release --example bench        # default depth
+//! cargo run --release --example bench -- 12
+//! ```
+
+use chessai::BENCH_DEPTH;
+use chessai::BENCH_FENS;
+
+fn main() {
+    let depth = match std::env::args().nth(1) {
+        None => BENCH_DEPTH,
+        Some(arg) => arg.parse().unwrap_or_else(|_| {
+            eprintln!("usage: bench [depth]");
+            std::process::exit(2);
+        }),
+    };
+    let report = chessai::bench_with(depth, |idx, info| {
+        eprintln!("position {:>2}/{}: {:>10} nodes  {}", idx + 1, BENCH_FENS.len(), info.nodes, BENCH_FENS[idx]);
+    });
+    println!("===========================");
+    println!("Total time (ms) : {}", report.time.as_millis());
+    println!("Nodes searched  : {}", report.nodes);
+    println!("Nodes/second    : {}", report.nps);
+}
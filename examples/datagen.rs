@@ -0,0 +1,75 @@
+//! Generate self-play training data.
+//!
+//! Positional arguments are the output file, the number of games, the node budget per
+//! move and the number of concurrent games. `--book` opens from the embedded book instead
+//! of 8 random plies (`--random <plies>` changes the count); `--keep-checks` and
+//! `--keep-captures` disable the corresponding filters. The output loads directly as
+//! `texel_tune` input.
+//!
+//! ```sh
+//! cargo run --release --example datagen -- selfplay.txt 10000 5000 8
+//! cargo run --release --example texel_tune -- selfplay.txt 500 > tuned.rs
+//! ```
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+
+use chessai::DataGenerator;
+use chessai::Limits;
+use chessai::Opening;
+
+fn main() {
+    let mut generator = DataGenerator::new();
+    let mut positional = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--book" => generator = generator.opening(Opening::Book),
+            "--random" => {
+                let plies = args.next().and_then(|s| s.parse().ok()).unwrap_or(8);
+                generator = generator.opening(Opening::RandomPlies(plies));
+            }
+            "--keep-checks" => generator = generator.skip_in_check(false),
+            "--keep-captures" => generator = generator.skip_captures(false),
+            _ => positional.push(arg),
+        }
+    }
+    let mut positional = positional.into_iter();
+    let Some(path) = positional.next() else {
+        eprintln!(
+            "usage: datagen <output-file> [games] [nodes] [threads] [--book | --random <plies>] [--keep-checks] [--keep-captures]"
+        );
+        std::process::exit(2);
+    };
+    let mut number = |default: u64| positional.next().and_then(|s| s.parse().ok()).unwrap_or(default);
+    let games = number(100) as u32;
+    let nodes = number(5000);
+    let threads = number(1) as usize;
+
+    let file = File::create(&path).unwrap_or_else(|e| {
+        eprintln!("cannot create {path}: {e}");
+        std::process::exit(1);
+    });
+    let generator = generator.games(games).limits(Limits::new().nodes(nodes)).threads(threads);
+    let mut done = 0;
+    let mut out = BufWriter::new(file);
+    let mut failed = None;
+    let written = generator.run(|records| {
+        done += 1;
+        for record in records {
+            if failed.is_none()
+                && let Err(e) = writeln!(out, "{record}")
+            {
+                failed = Some(e);
+            }
+        }
+        eprint!("\rgame {done}/{games}");
+    });
+    eprintln!();
+    if let Some(e) = failed.or_else(|| out.flush().err()) {
+        eprintln!("cannot write {path}: {e}");
+        std::process::exit(1);
+    }
+    eprintln!("{written} positions written to {path}");
+}
@@ -0,0 +1,112 @@
+//! Play two engine configurations against each other and report W/D/L, Elo and an SPRT
+//! verdict.
+//!
+//! Each side is configured by `--a-*` / `--b-*` flags: `search <params-file>`,
+//! `eval <params-file>`, `hash <mb>` and `threads <n>`. Games use `--nodes` or `--depth`
+//! per move, openings come from `--openings <file>` (one FEN per line) or random plies.
+//! Progress goes to stderr, the final report to stdout.
+//!
+//! ```sh
+//! cargo run --release --example engine_match -- --a-search tuned.search --games 2000 \
+//!     --nodes 5000 --concurrency 8 --sprt 0 5
+//! ```
+
+use std::collections::HashMap;
+
+use chessai::Engine;
+use chessai::EngineMatch;
+use chessai::EvalParams;
+use chessai::Limits;
+use chessai::SearchParams;
+use chessai::Sprt;
+
+const USAGE: &str = "usage: engine_match [--a-search|--b-search <file>] [--a-eval|--b-eval <file>] \
+                     [--a-hash|--b-hash <mb>] [--a-threads|--b-threads <n>] [--games <n>] \
+                     [--nodes <n> | --depth <n>] [--concurrency <n>] [--openings <file>] [--sprt <elo0> <elo1>]";
+
+/// Every flag the example takes, without the leading `--`.
+const FLAGS: [&str; 14] = [
+    "a-search",
+    "b-search",
+    "a-eval",
+    "b-eval",
+    "a-hash",
+    "b-hash",
+    "a-threads",
+    "b-threads",
+    "games",
+    "nodes",
+    "depth",
+    "concurrency",
+    "openings",
+    "sprt",
+];
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}");
+    std::process::exit(2);
+}
+
+fn read(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|e| fail(&format!("cannot read {path}: {e}")))
+}
+
+fn number<T: std::str::FromStr>(flags: &HashMap<String, Vec<String>>, key: &str) -> Option<T> {
+    flags.get(key).map(|v| v[0].parse().unwrap_or_else(|_| fail(&format!("--{key} expects a number"))))
+}
+
+/// An engine factory for side `a` or `b`.
+fn side(flags: &HashMap<String, Vec<String>>, name: &str) -> impl Fn() -> Engine + Send + Sync + 'static {
+    let search: SearchParams = flags.get(&format!("{name}-search")).map_or_else(SearchParams::default, |v| {
+        read(&v[0]).parse().unwrap_or_else(|e| fail(&format!("{}: {e}", v[0])))
+    });
+    let eval: EvalParams = flags
+        .get(&format!("{name}-eval"))
+        .map_or_else(EvalParams::default, |v| read(&v[0]).parse().unwrap_or_else(|e| fail(&format!("{}: {e}", v[0]))));
+    let hash = number(flags, &format!("{name}-hash")).unwrap_or(16);
+    let threads = number(flags, &format!("{name}-threads")).unwrap_or(1);
+    move || {
+        Engine::builder()
+            .use_book(false)
+            .search_params(search.clone())
+            .eval_params(eval.clone())
+            .hash_size(hash)
+            .threads(threads)
+            .build()
+    }
+}
+
+fn main() {
+    let mut flags: HashMap<String, Vec<String>> = HashMap::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let Some(key) = arg.strip_prefix("--").filter(|key| FLAGS.contains(key)) else { fail(USAGE) };
+        let arity = if key == "sprt" { 2 } else { 1 };
+        let values: Vec<String> = args.by_ref().take(arity).collect();
+        if values.len() != arity {
+            fail(USAGE);
+        }
+        flags.insert(key.to_string(), values);
+    }
+
+    let limits = match number::<u8>(&flags, "depth") {
+        Some(depth) => Limits::new().depth(depth),
+        None => Limits::new().nodes(number(&flags, "nodes").unwrap_or(10_000)),
+    };
+    let mut runner = EngineMatch::new(side(&flags, "a"), side(&flags, "b"))
+        .games(number(&flags, "games").unwrap_or(100))
+        .limits(limits)
+        .concurrency(number(&flags, "concurrency").unwrap_or(1));
+    if let Some(path) = flags.get("openings") {
+        let text = read(&path[0]);
+        runner = runner.openings(text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')));
+    }
+    if let Some(bounds) = flags.get("sprt") {
+        let elo: Vec<f64> = bounds.iter().map(|v| v.parse().unwrap_or_else(|_| fail(USAGE))).collect();
+        runner = runner.sprt(Sprt::new(elo[0], elo[1]));
+    }
+
+    let report = runner.run(|report| eprint!("\r{report}  ")).unwrap_or_else(|e| fail(&e.to_string()));
+    eprintln!();
+    println!("{report}");
+}
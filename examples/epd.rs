@@ -0,0 +1,55 @@
+//! Run an EPD test suite and print one result line per position plus a `total` line.
+//!
+//! Arguments are the suite file, then `--depth <n>`, `--nodes <n>` or `--time <ms>` per
+//! position (default 1000 ms), and optionally `--threads <n>` / `--hash <mb>`. Lines that
+//! are empty or start with `#` are skipped. The exit code is 1 if any position failed.
+//!
+//! ```sh
+//! cargo run --release --example epd -- tactics.epd --time 2000 > today.txt
+//! diff yesterday.txt today.txt
+//! ```
+
+use std::time::Duration;
+
+use chessai::Engine;
+use chessai::EpdRecord;
+use chessai::Limits;
+use chessai::run_epd;
+
+const USAGE: &str = "usage: epd <suite-file> [--depth <n> | --nodes <n> | --time <ms>] [--threads <n>] [--hash <mb>]";
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}");
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| fail(USAGE));
+    let (mut limits, mut threads, mut hash) = (Limits::new().time(Duration::from_millis(1000)), 1, 16);
+    while let Some(flag) = args.next() {
+        let value: u64 = args.next().and_then(|v| v.parse().ok()).unwrap_or_else(|| fail(USAGE));
+        match flag.as_str() {
+            "--depth" => limits = Limits::new().depth(value as u8),
+            "--nodes" => limits = Limits::new().nodes(value),
+            "--time" => limits = Limits::new().time(Duration::from_millis(value)),
+            "--threads" => threads = value as u8,
+            "--hash" => hash = value as usize,
+            _ => fail(USAGE),
+        }
+    }
+
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|e| fail(&format!("cannot read {path}: {e}")));
+    let records: Vec<EpdRecord> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.parse().unwrap_or_else(|e| fail(&format!("{path}: {e}"))))
+        .collect();
+    let mut engine = Engine::builder().use_book(false).threads(threads).hash_size(hash).build();
+    let report = run_epd(&mut engine, &records, limits, |result| eprintln!("{result}"));
+    println!("{report}");
+    if report.solved() < report.results.len() {
+        std::process::exit(1);
+    }
+}
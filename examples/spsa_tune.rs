@@ -0,0 +1,60 @@
+//! Tune the search parameters by SPSA self-play.
+//!
+//! Arguments are the iteration count, the node budget per move and the number of
+//! concurrent game pairs, optionally followed by `--start <params-file>` and any number of
+//! parameter names to restrict the tuning to. Progress goes to stderr; the tuned
+//! parameters are printed to stdout as a file for `EngineBuilder::search_params`.
+//!
+//! ```sh
+//! cargo run --release --example spsa_tune -- 500 2000 8 > tuned.search
+//! cargo run --release --example spsa_tune -- 200 2000 8 rfp_margin razor_margin > tuned.search
+//! ```
+
+use chessai::Limits;
+use chessai::SearchParams;
+use chessai::SpsaTuner;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut number = |default: u64| args.next().and_then(|s| s.parse().ok()).unwrap_or(default);
+    let iterations = number(100) as u32;
+    let nodes = number(1000);
+    let threads = number(1) as usize;
+
+    let mut start = SearchParams::default();
+    let mut names = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "--start" {
+            let Some(path) = args.next() else {
+                eprintln!("usage: spsa_tune [iterations] [nodes] [threads] [--start <params-file>] [names…]");
+                std::process::exit(2);
+            };
+            let text = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+                eprintln!("cannot read {path}: {e}");
+                std::process::exit(1);
+            });
+            start = text.parse().unwrap_or_else(|e| {
+                eprintln!("{path}: {e}");
+                std::process::exit(1);
+            });
+        } else {
+            names.push(arg);
+        }
+    }
+
+    let mut tuner = SpsaTuner::new(start).iterations(iterations).limits(Limits::new().nodes(nodes)).threads(threads);
+    if !names.is_empty() {
+        tuner = tuner.tune(&names.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+
+    let report_every = (iterations / 20).max(1);
+    let tuned = tuner.run(|iteration, params| {
+        if iteration % report_every == 0 {
+            eprintln!("iteration {iteration:>5}:");
+            for line in params.to_string().lines().skip(1) {
+                eprintln!("    {line}");
+            }
+        }
+    });
+    print!("{tuned}");
+}
@@ -0,0 +1,63 @@
+//! Tune the evaluation tables from a file of labelled positions.
+//!
+//! Each input line is `<fen> | <result>` with the result from red's point of view
+//! (`1-0`, `0-1`, `1/2-1/2` or a number in `0..=1`). Progress goes to stderr; the tuned
+//! tables are printed to stdout as Rust source ready to paste into `src/eval.rs`, or with
+//! `--params` as a parameter file for `EngineBuilder::eval_params`.
+//!
+//! ```sh
+//! cargo run --release --example texel_tune -- positions.txt 500 > tuned.rs
+//! cargo run --release --example texel_tune -- positions.txt 500 1.0 --params > tuned.params
+//! ```
+
+use chessai::TexelTuner;
+use chessai::TuningPosition;
+
+fn main() {
+    let (flags, positional): (Vec<String>, Vec<String>) = std::env::args().skip(1).partition(|a| a.starts_with("--"));
+    let as_params = flags.iter().any(|f| f == "--params");
+    let mut args = positional.into_iter();
+    let Some(path) = args.next() else {
+        eprintln!("usage: texel_tune <positions-file> [epochs] [learning-rate] [--params]");
+        std::process::exit(2);
+    };
+    let epochs: u32 = args.next().and_then(|s| s.parse().ok()).unwrap_or(200);
+    let learning_rate: f64 = args.next().and_then(|s| s.parse().ok()).unwrap_or(1.0);
+
+    let data = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("cannot read {path}: {e}");
+        std::process::exit(1);
+    });
+    let mut skipped = 0usize;
+    let positions: Vec<TuningPosition> = data
+        .lines()
+        .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+        .filter_map(|l| match l.parse() {
+            Ok(p) => Some(p),
+            Err(_) => {
+                skipped += 1;
+                None
+            }
+        })
+        .collect();
+    eprintln!("loaded {} positions ({skipped} unparsable lines skipped)", positions.len());
+
+    let mut tuner = TexelTuner::new(&positions).epochs(epochs).learning_rate(learning_rate);
+    eprintln!("{} quiet leaves after quiescence", tuner.len());
+    let k = tuner.fit_scaling();
+    eprintln!("scaling k = {k:.4}, initial error = {:.6}", tuner.error());
+
+    let report_every = (epochs / 20).max(1);
+    let final_error = tuner.run(|epoch, err| {
+        if epoch % report_every == 0 {
+            eprintln!("epoch {epoch:>5}: error {err:.6}");
+        }
+    });
+    eprintln!("final error = {final_error:.6}");
+
+    if as_params {
+        print!("{}", tuner.eval_params());
+    } else {
+        print!("{}", tuner.to_rust_source());
+    }
+}
@@ -0,0 +1,88 @@
+//! Fixed-depth search over a fixed position suite. The total node count is a signature of
+//! the search: a change that alters it changes what the engine does, one that keeps it is
+//! functionally neutral (speed aside).
+//!
+//! ```no_run
+//! let report = chessai::bench(chessai::BENCH_DEPTH);
+//! println!("{} nodes {} nps", report.nodes, report.nps);
+//! ```
+
+use std::time::Duration;
+
+use crate::engine::Engine;
+use crate::fen::STARTING_FEN;
+use crate::limits::Limits;
+use crate::search::SearchInfo;
+
+/// Default search depth of [`bench`].
+pub const BENCH_DEPTH: u8 = 10;
+
+/// Hash size of the bench engine. Part of the signature: node counts depend on it.
+const BENCH_HASH_MB: usize = 16;
+
+/// Openings, middlegames and endgames, each searched from an empty hash.
+pub const BENCH_FENS: [&str; 14] = [
+    STARTING_FEN,
+    "r1bakab1r/9/1cn4cn/p1p1p1p1p/9/9/P1P1P1P1P/1CN4C1/9/R1BAKABNR w",
+    "rnbakabn1/9/c7r/p1p1p1p1p/9/4P4/P1P3P1P/5C3/7C1/RNBAKABNR w",
+    "r3ka3/4a4/bcn3r1b/1C1Rp1p1p/p1p6/9/P1P1P1P1P/2N1B4/9/2BAKA1R1 w",
+    "2bak1b2/n3a4/3r2n2/p1p3p1p/4p4/9/2N1R1P1P/4C4/9/2BAKAB2 b",
+    "3akab2/9/2n1b1nc1/p1N1p1p1p/2P2R3/9/4r1P1P/7C1/1C7/2BAKAB2 w",
+    "3ak1b2/4a2r1/9/pR3P2p/9/4RN1r1/n1p5P/9/9/2BAKAB2 w",
+    "2ck1a3/4a1N2/b7b/1R6C/4P4/p3r1B2/8P/4B4/4A4/3A1K3 b",
+    "3a1kb2/4a4/4b1P2/1N7/p8/9/1n7/2C6/4A4/2B1K1p2 w",
+    "3R1a3/4k4/b8/p2Pp3p/6p2/9/P7P/4B1Cr1/4c4/2BK5 w",
+    "9/4ak3/c8/9/6b2/9/8P/B3B4/9/3KC4 w",
+    "7N1/9/3Rrk3/6C2/9/6B2/9/4B4/2p1A4/3AK4 w",
+    "3k5/9/9/9/9/9/9/9/4C4/R3K4 w",
+    "2bak4/4a4/4b4/9/9/9/9/4B4/4A4/2BAK1R2 w",
+];
+
+/// Outcome of a [`bench`] run.
+#[derive(Clone, Debug, Default)]
+pub struct BenchReport {
+    /// Final search result of each [`BENCH_FENS`] position, in order.
+    pub results: Vec<SearchInfo>,
+    /// Total nodes: the search signature.
+    pub nodes: u64,
+    pub time: Duration,
+    pub nps: u64,
+}
+
+/// Search every [`BENCH_FENS`] position to `depth` on one thread, without a book.
+pub fn bench(depth: u8) -> BenchReport { bench_with(depth, |_, _| {}) }
+
+/// [`bench`], passing each position's index and result to `progress` as it finishes.
+pub fn bench_with(depth: u8, mut progress: impl FnMut(usize, &SearchInfo)) -> BenchReport {
+    let mut engine = Engine::builder().threads(1).use_book(false).hash_size(BENCH_HASH_MB).build();
+    let mut report = BenchReport::default();
+    for (idx, fen) in BENCH_FENS.iter().enumerate() {
+        engine.set_fen(fen).expect("bench FENs parse");
+        let info = engine.search(Limits::new().depth(depth));
+        report.nodes += info.nodes;
+        report.time += info.time;
+        progress(idx, &info);
+        report.results.push(info);
+    }
+    let secs = report.time.as_secs_f64();
+    report.nps = if secs > 0.0 { (report.nodes as f64 / secs) as u64 } else { 0 };
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_count_is_a_stable_signature() {
+        let mut seen = 0;
+        let report = bench_with(3, |idx, _| {
+            assert_eq!(idx, seen);
+            seen += 1;
+        });
+        assert_eq!(report.results.len(), BENCH_FENS.len());
+        assert_eq!(report.nodes, report.results.iter().map(|info| info.nodes).sum::<u64>());
+        assert!(report.results.iter().all(|info| info.best_move.is_some()));
+        assert_eq!(bench(3).nodes, report.nodes);
+    }
+}
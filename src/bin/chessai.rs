@@ -0,0 +1,468 @@
+//! `chessai` command-line tool.
+//!
+//! ```sh
+//! chessai analyse "<fen>" --depth 12        # stream one line per iteration
+//! chessai bestmove --time 2000              # startpos when no FEN is given
+//! chessai hint "<fen>" --depth 10
+//! chessai legal "<fen>" --wxf
+//! chessai show "<fen>"
+//! chessai svg "<fen>" --arrow h2e2 > diagram.svg
+//! chessai play --black --time 3000          # human vs engine, `undo` takes back
+//! chessai convert game.pgn --to wxf
+//! chessai review game.pgn --depth 10
+//! chessai bench 12                          # node-count signature of the search
+//! chessai compact analysis.cache            # drop stale records from an analysis cache
+//! ```
+//!
+//! Exit codes: `0` success, `1` no result (no legal move, or a game that does not
+//! convert), `2` bad usage or unparsable input.
+
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Write;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use chessai::AnalysisCache;
+use chessai::BENCH_DEPTH;
+use chessai::BENCH_FENS;
+use chessai::ChessAIError;
+use chessai::Color;
+use chessai::Engine;
+use chessai::Game;
+use chessai::GameStatus;
+use chessai::Glyphs;
+use chessai::Limits;
+use chessai::Move;
+use chessai::Position;
+use chessai::RenderStyle;
+use chessai::STARTING_FEN;
+use chessai::ScoreBound;
+use chessai::SearchInfo;
+use chessai::SvgStyle;
+
+const USAGE: &str = "usage: chessai <command> [fen | file] [options]
+
+commands:
+  analyse [fen]    search and print one line per iteration, then `bestmove`
+  bestmove [fen]   print only the best move
+  hint [fen]       candidate moves with what they do, and the opponent's threat
+  legal [fen]      list the legal moves
+  show [fen]       print the board
+  svg [fen]        print an SVG diagram of the board
+  play [fen]       play against the engine; type moves, `undo`, `moves` or `quit`
+  convert [file]   read a game (ICCS, WXF or PGN; stdin by default) and rewrite it
+  review [file]    grade every move of a game against the engine's choice
+  bench [depth]    search the bench positions on one thread; print total nodes and speed
+  compact <file>   rewrite an analysis cache with only its live entries
+
+options:
+  --depth <n> | --nodes <n> | --time <ms>   search limits (default 1000 ms)
+  --multipv <n>                             lines reported by `analyse`
+  --threads <n>  --hash <mb>  --book        engine settings
+  --wxf                                     print moves in WXF notation
+  --black                                   `play`: the human takes black
+  --chinese  --flip                         `show` / `play`: Chinese glyphs, black at the bottom
+  --size <px>  --arrow <move>               `svg`: width, and arrows to draw (repeatable)
+  --fen <fen>                               `convert`: start position of a bare move list
+  --to iccs|wxf|pgn                         `convert`: output format (default pgn)";
+
+/// Command-line failure: a message and the exit code to leave with.
+struct Failure(String, u8);
+
+fn usage(message: impl Into<String>) -> Failure { Failure(message.into(), 2) }
+
+struct Options {
+    positional: Vec<String>,
+    limits: Limits,
+    multipv: u8,
+    threads: u8,
+    hash_mb: usize,
+    book: bool,
+    wxf: bool,
+    black: bool,
+    chinese: bool,
+    flip: bool,
+    fen: Option<String>,
+    to: String,
+    size: u32,
+    arrows: Vec<String>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, Failure> {
+        let mut options = Options {
+            positional: Vec::new(),
+            limits: Limits::new().time(Duration::from_millis(1000)),
+            multipv: 1,
+            threads: 1,
+            hash_mb: 64,
+            book: false,
+            wxf: false,
+            black: false,
+            chinese: false,
+            flip: false,
+            fen: None,
+            to: "pgn".to_string(),
+            size: 400,
+            arrows: Vec::new(),
+        };
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                options.positional.push(arg);
+                continue;
+            };
+            match flag {
+                "book" => options.book = true,
+                "wxf" => options.wxf = true,
+                "black" => options.black = true,
+                "chinese" => options.chinese = true,
+                "flip" => options.flip = true,
+                _ => {
+                    let value = args.next().ok_or_else(|| usage(format!("--{flag} needs a value")))?;
+                    let number = || value.parse::<u64>().map_err(|_| usage(format!("--{flag} expects a number")));
+                    match flag {
+                        "depth" => options.limits = Limits::new().depth(number()?.min(u8::MAX as u64) as u8),
+                        "nodes" => options.limits = Limits::new().nodes(number()?),
+                        "time" => options.limits = Limits::new().time(Duration::from_millis(number()?)),
+                        "multipv" => options.multipv = number()?.clamp(1, u8::MAX as u64) as u8,
+                        "threads" => options.threads = number()?.clamp(1, u8::MAX as u64) as u8,
+                        "hash" => options.hash_mb = number()? as usize,
+                        "fen" => options.fen = Some(value),
+                        "to" => options.to = value,
+                        "size" => options.size = number()?.min(u32::MAX as u64) as u32,
+                        "arrow" => options.arrows.push(value),
+                        _ => return Err(usage(format!("unknown option --{flag}"))),
+                    }
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    fn engine(&self) -> Engine {
+        Engine::builder().use_book(self.book).threads(self.threads).hash_size(self.hash_mb).build()
+    }
+
+    /// The engine set up on the positional FEN (its fields may arrive as separate
+    /// arguments), or the start position.
+    fn engine_at_fen(&self) -> Result<Engine, Failure> {
+        let fen = match self.positional.join(" ") {
+            fen if fen.is_empty() || fen == "startpos" => STARTING_FEN.to_string(),
+            fen => fen,
+        };
+        let mut engine = self.engine();
+        engine.set_fen(&fen).map_err(|e| usage(format!("bad FEN: {e}")))?;
+        Ok(engine)
+    }
+
+    fn render(&self, engine: &Engine, orientation: Color) -> String {
+        let glyphs = if self.chinese { Glyphs::Chinese } else { Glyphs::Ascii };
+        let orientation = if self.flip { orientation.flip() } else { orientation };
+        let style = RenderStyle::new().glyphs(glyphs).orientation(orientation).last_move(engine.move_history().last());
+        engine.position().render(style)
+    }
+
+    fn format_move(&self, pos: &Position, mv: Move) -> String {
+        if self.wxf { pos.move_to_wxf(mv) } else { mv.to_iccs() }
+    }
+}
+
+/// A move in ICCS or WXF notation, if legal in `pos`.
+fn parse_move(pos: &Position, text: &str) -> Option<Move> {
+    Move::from_iccs(text).ok().filter(|&mv| pos.is_legal(mv)).or_else(|| pos.parse_wxf(text).ok())
+}
+
+/// `pv` in WXF, playing it out on a copy of `pos`.
+fn wxf_line(pos: &Position, pv: &[Move]) -> Vec<String> {
+    let mut pos = pos.clone();
+    pv.iter()
+        .map(|&mv| {
+            let text = pos.move_to_wxf(mv);
+            pos.try_make_move(mv);
+            text
+        })
+        .collect()
+}
+
+/// `score` as `mate <n>` when it is the mate score the search reported, else `cp <score>`.
+fn score_text(info: &SearchInfo, score: i32) -> String {
+    match info.mate_in {
+        Some(moves) if score == info.score => format!("mate {moves}"),
+        _ => format!("cp {score}"),
+    }
+}
+
+fn analyse(options: &Options) -> Result<(), Failure> {
+    let mut engine = options.engine_at_fen()?;
+    let pos = engine.position().clone();
+    let moves = |pv: &[Move]| match options.wxf {
+        true => wxf_line(&pos, pv).join(" "),
+        false => pv.iter().map(|mv| mv.to_iccs()).collect::<Vec<_>>().join(" "),
+    };
+    let info = engine.search_with(options.limits.multipv(options.multipv), |info| {
+        let bound = match info.bound {
+            ScoreBound::Exact => "",
+            ScoreBound::Lower => " lowerbound",
+            ScoreBound::Upper => " upperbound",
+        };
+        let head = format!(
+            "depth {} seldepth {} nodes {} nps {} time {}",
+            info.depth,
+            info.seldepth,
+            info.nodes,
+            info.nps,
+            info.time.as_millis()
+        );
+        if info.lines.len() > 1 {
+            for (k, line) in info.lines.iter().enumerate() {
+                println!(
+                    "{head} multipv {} score {}{bound} pv {}",
+                    k + 1,
+                    score_text(info, line.score),
+                    moves(&line.pv)
+                );
+            }
+        } else {
+            println!("{head} score {}{bound} pv {}", score_text(info, info.score), moves(&info.pv));
+        }
+    });
+    let best = info.best_move.ok_or(Failure("no legal move".to_string(), 1))?;
+    println!("bestmove {}", options.format_move(&pos, best));
+    Ok(())
+}
+
+fn bestmove(options: &Options) -> Result<(), Failure> {
+    let mut engine = options.engine_at_fen()?;
+    let info = engine.search(options.limits);
+    let best = info.best_move.ok_or(Failure("no legal move".to_string(), 1))?;
+    println!("{}", options.format_move(engine.position(), best));
+    Ok(())
+}
+
+fn hint(options: &Options) -> Result<(), Failure> {
+    let mut engine = options.engine_at_fen()?;
+    let pos = engine.position().clone();
+    let hint = engine.hint(options.limits.multipv(options.multipv));
+    if let Some(threat) = &hint.threat {
+        let mut what = Vec::new();
+        if let Some(n) = threat.mate_in {
+            what.push(format!("mate in {n}"));
+        }
+        if let Some(kind) = threat.captures {
+            what.push(format!("takes {kind:?}").to_lowercase());
+        }
+        if threat.gives_check {
+            what.push("check".to_string());
+        }
+        println!("threat {} ({})", options.format_move(&pos, threat.mv), what.join(", "));
+    }
+    for (k, candidate) in hint.candidates.iter().enumerate() {
+        let mut what = Vec::new();
+        if let Some(kind) = candidate.captures {
+            what.push(format!("takes {kind:?}").to_lowercase());
+        }
+        if candidate.gives_check {
+            what.push("check".to_string());
+        }
+        if candidate.defends {
+            what.push("defends".to_string());
+        }
+        println!(
+            "{} {} score {} pv {}{}",
+            k + 1,
+            options.format_move(&pos, candidate.mv),
+            candidate.score,
+            candidate.pv.iter().map(|mv| mv.to_iccs()).collect::<Vec<_>>().join(" "),
+            if what.is_empty() { String::new() } else { format!(" ({})", what.join(", ")) }
+        );
+    }
+    if hint.candidates.is_empty() { Err(Failure("no legal move".to_string(), 1)) } else { Ok(()) }
+}
+
+fn legal(options: &Options) -> Result<(), Failure> {
+    let mut engine = options.engine_at_fen()?;
+    let moves = engine.legal_moves();
+    for &mv in &moves {
+        println!("{}", options.format_move(engine.position(), mv));
+    }
+    if moves.is_empty() { Err(Failure(String::new(), 1)) } else { Ok(()) }
+}
+
+fn show(options: &Options) -> Result<(), Failure> {
+    let engine = options.engine_at_fen()?;
+    print!("{}", options.render(&engine, Color::Red));
+    println!("{} to move", engine.side_to_move());
+    Ok(())
+}
+
+fn svg(options: &Options) -> Result<(), Failure> {
+    let engine = options.engine_at_fen()?;
+    let orientation = if options.flip { Color::Black } else { Color::Red };
+    let mut style = SvgStyle::new().size(options.size).orientation(orientation);
+    for text in &options.arrows {
+        let mv = parse_move(engine.position(), text).ok_or_else(|| usage(format!("not a legal move: {text}")))?;
+        style = style.arrow(mv);
+    }
+    print!("{}", engine.position().to_svg(&style));
+    Ok(())
+}
+
+fn play(options: &Options) -> Result<(), Failure> {
+    let mut engine = options.engine_at_fen()?;
+    let human = if options.black { Color::Black } else { Color::Red };
+    let mut input = std::io::stdin().lock().lines();
+    loop {
+        print!("\n{}", options.render(&engine, human));
+        let status = engine.game_status();
+        if status.is_over() {
+            println!(
+                "{}",
+                match status {
+                    GameStatus::Mate { winner } if winner == human => "you win",
+                    GameStatus::Mate { .. } => "you lose",
+                    _ => "draw",
+                }
+            );
+            return Ok(());
+        }
+        if engine.side_to_move() != human {
+            let pos = engine.position().clone();
+            let info = engine.search(options.limits);
+            let mv = info.best_move.ok_or(Failure("engine found no move".to_string(), 1))?;
+            println!("engine plays {} ({})", mv.to_iccs(), pos.move_to_wxf(mv));
+            engine.make_move(mv);
+            continue;
+        }
+        loop {
+            print!("your move> ");
+            std::io::stdout().flush().map_err(|e| Failure(e.to_string(), 1))?;
+            let Some(Ok(line)) = input.next() else { return Ok(()) };
+            match line.trim() {
+                "" => continue,
+                "quit" | "exit" => return Ok(()),
+                "moves" => {
+                    let pos = engine.position().clone();
+                    let list: Vec<String> = engine
+                        .legal_moves()
+                        .into_iter()
+                        .map(|mv| format!("{} ({})", mv, pos.move_to_wxf(mv)))
+                        .collect();
+                    println!("{}", list.join("  "));
+                }
+                "undo" => {
+                    // It is our move, so the last move is the engine's reply: take it back
+                    // together with our own move before it, if we have made one.
+                    if engine.move_history().count() < 2 {
+                        println!("nothing to take back");
+                        continue;
+                    }
+                    engine.undo_move();
+                    engine.undo_move();
+                    break;
+                }
+                text => match parse_move(engine.position(), text) {
+                    Some(mv) => {
+                        engine.make_move(mv);
+                        break;
+                    }
+                    None => println!("not a legal move: {text} (ICCS like h2e2 or WXF like C2.5)"),
+                },
+            }
+        }
+    }
+}
+
+/// The game in the positional file, or on stdin.
+fn input_game(options: &Options) -> Result<Game, Failure> {
+    let mut text = String::new();
+    let read = match options.positional.first().map(String::as_str) {
+        None | Some("-") => std::io::stdin().read_to_string(&mut text).map(drop),
+        Some(path) => std::fs::read_to_string(path).map(|t| text = t),
+    };
+    read.map_err(|e| usage(format!("cannot read input: {e}")))?;
+    Game::parse(&text, options.fen.as_deref()).map_err(|e| match e {
+        ChessAIError::BadGameMove { .. } => Failure(e.to_string(), 1),
+        e => usage(format!("bad FEN: {e}")),
+    })
+}
+
+fn review(options: &Options) -> Result<(), Failure> {
+    let game = input_game(options)?;
+    let mut engine = options.engine();
+    engine.set_fen(&game.start.to_fen()).map_err(|e| usage(format!("bad FEN: {e}")))?;
+    let review = engine.analyse_game(&game.moves, options.limits).map_err(|e| Failure(e.to_string(), 1))?;
+    print!("{review}");
+    Ok(())
+}
+
+fn convert(options: &Options) -> Result<(), Failure> {
+    let game = input_game(options)?;
+    match options.to.as_str() {
+        "iccs" => println!("{}", game.moves.iter().map(|mv| mv.to_iccs()).collect::<Vec<_>>().join(" ")),
+        "wxf" => println!("{}", wxf_line(&game.start, &game.moves).join(" ")),
+        "pgn" => println!("{game}"),
+        other => return Err(usage(format!("unknown format {other:?}; expected iccs, wxf or pgn"))),
+    }
+    Ok(())
+}
+
+fn bench(options: &Options) -> Result<(), Failure> {
+    let depth = match options.positional.as_slice() {
+        [] => BENCH_DEPTH,
+        [depth] => depth.parse().map_err(|_| usage(format!("bench depth must be a number, got {depth:?}")))?,
+        _ => return Err(usage("usage: chessai bench [depth]")),
+    };
+    let report = chessai::bench_with(depth, |idx, info| {
+        eprintln!("position {:>2}/{}: {:>10} nodes  {}", idx + 1, BENCH_FENS.len(), info.nodes, BENCH_FENS[idx]);
+    });
+    println!("===========================");
+    println!("Total time (ms) : {}", report.time.as_millis());
+    println!("Nodes searched  : {}", report.nodes);
+    println!("Nodes/second    : {}", report.nps);
+    Ok(())
+}
+
+fn compact(options: &Options) -> Result<(), Failure> {
+    let [path] = options.positional.as_slice() else { return Err(usage("usage: chessai compact <cache file>")) };
+    if !std::path::Path::new(path).is_file() {
+        return Err(usage(format!("no such cache file: {path}")));
+    }
+    let mut cache = AnalysisCache::open(path, usize::MAX).map_err(|e| Failure(e.to_string(), 1))?;
+    let stale = cache.stale_records();
+    cache.compact().map_err(|e| Failure(e.to_string(), 1))?;
+    println!("{} entries kept, {stale} stale records dropped", cache.len());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let command = args.next().unwrap_or_default();
+    let result = Options::parse(args).and_then(|options| match command.as_str() {
+        "analyse" | "analyze" => analyse(&options),
+        "bestmove" => bestmove(&options),
+        "hint" => hint(&options),
+        "legal" => legal(&options),
+        "show" => show(&options),
+        "svg" => svg(&options),
+        "play" => play(&options),
+        "convert" => convert(&options),
+        "review" => review(&options),
+        "bench" => bench(&options),
+        "compact" => compact(&options),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(usage(USAGE)),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure(message, code)) => {
+            if !message.is_empty() {
+                eprintln!("{message}");
+            }
+            ExitCode::from(code)
+        }
+    }
+}
@@ -38,7 +38,6 @@ impl BitBoard {
     #[inline]
     pub const fn any(self) -> bool { self.0 != 0 }
 
-    #[cfg(test)]
     #[inline]
     pub const fn popcount(self) -> u32 { self.0.count_ones() }
 
@@ -160,6 +159,25 @@ impl fmt::Display for BitBoard {
     }
 }
 
+/// Squares strictly between `a` and `b` when they share a rank or file; empty otherwise.
+pub fn between(a: Square, b: Square) -> BitBoard {
+    let (lo, hi) = if a.raw() < b.raw() { (a.raw(), b.raw()) } else { (b.raw(), a.raw()) };
+    let step = if a.rank() == b.rank() {
+        1
+    } else if a.file() == b.file() {
+        9
+    } else {
+        return BitBoard::EMPTY;
+    };
+    let mut bb = BitBoard::EMPTY;
+    let mut sq = lo + step;
+    while sq < hi {
+        bb.0 |= 1u128 << sq as u32;
+        sq += step;
+    }
+    bb
+}
+
 // -------- Board region masks --------
 
 const fn build_red_palace() -> BitBoard {
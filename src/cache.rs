@@ -0,0 +1,400 @@
+//! File-backed analysis cache: the final result of a search (depth, score, PV, nodes) per
+//! position key, so that servers re-analysing popular positions can skip the search.
+//!
+//! The file is an append-only log of records behind a small header. Opening replays the
+//! log into an in-memory index; every insert or eviction appends one record, and
+//! [`AnalysisCache::compact`] rewrites the file with only the live entries. A torn record
+//! at the end of the log (e.g. after a crash) is dropped on open; a damaged record
+//! anywhere else makes the open fail rather than discard the records after it.
+//!
+//! ```no_run
+//! use chessai::{AnalysisCache, Engine, Limits};
+//!
+//! let cache = AnalysisCache::open("analysis.cache", 100_000)?;
+//! let mut engine = Engine::builder().analysis_cache(cache).build();
+//! let info = engine.search(Limits::new().depth(10)); // served from the cache next time
+//! # let _ = info;
+//! # Ok::<(), chessai::ChessAIError>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::error::ChessAIError;
+use crate::mv::Move;
+use crate::util::Fnv64;
+use crate::zobrist::ZOBRIST;
+
+/// Leading bytes of a cache file.
+const CACHE_FILE_MAGIC: [u8; 8] = *b"CHESSAIA";
+/// Version of the record layout.
+const CACHE_FILE_VERSION: u32 = 2;
+/// Magic, version, Zobrist fingerprint.
+const HEADER_LEN: usize = 8 + 4 + 8;
+
+/// Fixed part of a put record, up to and including its head check.
+const PUT_HEAD_LEN: usize = 25;
+
+const RECORD_PUT: u8 = 1;
+const RECORD_REMOVE: u8 = 2;
+
+/// Longest PV kept per entry.
+const MAX_CACHED_PV: usize = u8::MAX as usize;
+
+/// One cached search result.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CachedAnalysis {
+    pub depth: u8,
+    pub score: i32,
+    pub pv: Vec<Move>,
+    pub nodes: u64,
+}
+
+pub struct AnalysisCache {
+    path: PathBuf,
+    file: File,
+    capacity: usize,
+    /// Key → (entry, recency tick).
+    index: HashMap<u64, (CachedAnalysis, u64)>,
+    /// Recency tick → key, oldest first; drives eviction.
+    recency: BTreeMap<u64, u64>,
+    tick: u64,
+    records: usize,
+}
+
+impl AnalysisCache {
+    /// Open (or create) the cache at `path`, holding at most `capacity` positions. When the
+    /// cache is full, the least recently used entry is evicted. Files written under a
+    /// different Zobrist layout are rejected, as are files with a record that fails its
+    /// checksum; only a record cut short at the end of the file is dropped.
+    pub fn open(path: impl AsRef<Path>, capacity: usize) -> Result<Self, ChessAIError> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let mut cache = AnalysisCache {
+            path,
+            file,
+            capacity: capacity.max(1),
+            index: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            records: 0,
+        };
+        if bytes.is_empty() {
+            cache.file.write_all(&header())?;
+            return Ok(cache);
+        }
+        if bytes.len() < HEADER_LEN || bytes[..HEADER_LEN] != header() {
+            return Err(ChessAIError::BadCacheFile("not a cache file for this build".into()));
+        }
+
+        let mut at = HEADER_LEN;
+        while at < bytes.len() {
+            match decode(&bytes[at..]) {
+                Decoded::Record((key, entry), len) => {
+                    match entry {
+                        Some(entry) => cache.put(key, entry),
+                        None => cache.forget(key),
+                    }
+                    cache.records += 1;
+                    at += len;
+                }
+                Decoded::Torn => {
+                    // Torn tail: keep everything up to the last complete record.
+                    cache.file.set_len(at as u64)?;
+                    break;
+                }
+                Decoded::Corrupt => {
+                    return Err(ChessAIError::BadCacheFile(format!("damaged record at byte {at}")));
+                }
+            }
+        }
+        cache.file.seek(SeekFrom::Start(at as u64))?;
+        Ok(cache)
+    }
+
+    /// Number of cached positions.
+    pub fn len(&self) -> usize { self.index.len() }
+
+    pub fn is_empty(&self) -> bool { self.index.is_empty() }
+
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Log records that [`compact`](Self::compact) would drop: overwritten entries,
+    /// evictions and removals.
+    pub fn stale_records(&self) -> usize { self.records - self.index.len() }
+
+    /// Look up `key`, marking the entry as recently used.
+    pub fn get(&mut self, key: u64) -> Option<&CachedAnalysis> {
+        self.tick += 1;
+        let (entry, tick) = self.index.get_mut(&key)?;
+        self.recency.remove(tick);
+        *tick = self.tick;
+        self.recency.insert(self.tick, key);
+        Some(entry)
+    }
+
+    /// Record a result for `key`, unless a deeper one is already cached. Evicts the least
+    /// recently used entry when full.
+    pub fn insert(&mut self, key: u64, mut entry: CachedAnalysis) -> Result<(), ChessAIError> {
+        if self.index.get(&key).is_some_and(|(old, _)| old.depth > entry.depth) {
+            return Ok(());
+        }
+        entry.pv.truncate(MAX_CACHED_PV);
+        if !self.index.contains_key(&key) && self.index.len() >= self.capacity {
+            // Log the eviction before the insert so that replay never has to evict itself.
+            let victim = *self.recency.values().next().expect("full cache has a recency entry");
+            self.append(&encode(victim, None))?;
+            self.forget(victim);
+        }
+        self.append(&encode(key, Some(&entry)))?;
+        self.put(key, entry);
+        Ok(())
+    }
+
+    /// Drop `key` from the cache.
+    pub fn remove(&mut self, key: u64) -> Result<(), ChessAIError> {
+        if self.index.contains_key(&key) {
+            self.append(&encode(key, None))?;
+            self.forget(key);
+        }
+        Ok(())
+    }
+
+    /// Rewrite the file with only the live entries, oldest first so that reopening keeps
+    /// the eviction order. The new file replaces the old one atomically.
+    pub fn compact(&mut self) -> Result<(), ChessAIError> {
+        let mut tmp = OsString::from(self.path.as_os_str());
+        tmp.push(".compact");
+        let tmp = PathBuf::from(tmp);
+
+        let mut out = header().to_vec();
+        for key in self.recency.values() {
+            out.extend_from_slice(&encode(*key, Some(&self.index[key].0)));
+        }
+        let mut file = File::create(&tmp)?;
+        file.write_all(&out)?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&tmp, &self.path)?;
+
+        self.file = OpenOptions::new().read(true).write(true).open(&self.path)?;
+        self.file.seek(SeekFrom::End(0))?;
+        self.records = self.index.len();
+        Ok(())
+    }
+
+    fn append(&mut self, record: &[u8]) -> Result<(), ChessAIError> {
+        self.file.write_all(record)?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Index-only insert (no I/O). Evicts silently when full, which only happens when
+    /// replaying a log into a smaller capacity.
+    fn put(&mut self, key: u64, entry: CachedAnalysis) {
+        self.forget(key);
+        if self.index.len() >= self.capacity
+            && let Some((_, victim)) = self.recency.pop_first()
+        {
+            self.index.remove(&victim);
+        }
+        self.tick += 1;
+        self.recency.insert(self.tick, key);
+        self.index.insert(key, (entry, self.tick));
+    }
+
+    fn forget(&mut self, key: u64) {
+        if let Some((_, tick)) = self.index.remove(&key) {
+            self.recency.remove(&tick);
+        }
+    }
+}
+
+fn header() -> [u8; HEADER_LEN] {
+    let mut h = [0u8; HEADER_LEN];
+    h[..8].copy_from_slice(&CACHE_FILE_MAGIC);
+    h[8..12].copy_from_slice(&CACHE_FILE_VERSION.to_le_bytes());
+    h[12..].copy_from_slice(&ZOBRIST.fingerprint().to_le_bytes());
+    h
+}
+
+/// Record layout, little-endian: kind `u8`, key `u64`, and for a put depth `u8`, score
+/// `i32`, nodes `u64`, PV length `u8`, the low 16 bits of an FNV-1a hash of those first 23
+/// bytes (so a damaged PV length is caught before it is trusted) and the PV moves as
+/// `u16`; then the low 32 bits of an FNV-1a hash of all the preceding bytes.
+fn encode(key: u64, entry: Option<&CachedAnalysis>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(32);
+    out.push(if entry.is_some() { RECORD_PUT } else { RECORD_REMOVE });
+    out.extend_from_slice(&key.to_le_bytes());
+    if let Some(entry) = entry {
+        out.push(entry.depth);
+        out.extend_from_slice(&entry.score.to_le_bytes());
+        out.extend_from_slice(&entry.nodes.to_le_bytes());
+        out.push(entry.pv.len() as u8);
+        let mut head = Fnv64::new();
+        head.update(&out);
+        out.extend_from_slice(&(head.finish() as u16).to_le_bytes());
+        for mv in &entry.pv {
+            out.extend_from_slice(&mv.raw().to_le_bytes());
+        }
+    }
+    let mut sum = Fnv64::new();
+    sum.update(&out);
+    out.extend_from_slice(&(sum.finish() as u32).to_le_bytes());
+    out
+}
+
+/// Outcome of decoding one record.
+enum Decoded {
+    /// An intact record and its encoded length.
+    Record((u64, Option<CachedAnalysis>), usize),
+    /// The bytes end before the record does: the tail of an interrupted append. Only
+    /// reported once the record's length is known to be intact.
+    Torn,
+    /// An unknown record kind or a checksum mismatch.
+    Corrupt,
+}
+
+/// Decode the record at the start of `bytes`.
+fn decode(bytes: &[u8]) -> Decoded {
+    let Some(&kind) = bytes.first() else {
+        return Decoded::Torn;
+    };
+    let body = match kind {
+        RECORD_PUT => {
+            let Some(head) = bytes.get(..PUT_HEAD_LEN) else {
+                return Decoded::Torn;
+            };
+            let mut sum = Fnv64::new();
+            sum.update(&head[..23]);
+            if sum.finish() as u16 != u16::from_le_bytes([head[23], head[24]]) {
+                return Decoded::Corrupt;
+            }
+            PUT_HEAD_LEN + 2 * head[22] as usize
+        }
+        RECORD_REMOVE => 9,
+        _ => return Decoded::Corrupt,
+    };
+    let Some(check) = bytes.get(body..body + 4) else {
+        return Decoded::Torn;
+    };
+    let mut sum = Fnv64::new();
+    sum.update(&bytes[..body]);
+    if sum.finish() as u32 != u32::from_le_bytes(check.try_into().expect("four bytes")) {
+        return Decoded::Corrupt;
+    }
+    let key = u64::from_le_bytes(bytes[1..9].try_into().expect("eight bytes"));
+    let entry = (kind == RECORD_PUT).then(|| CachedAnalysis {
+        depth: bytes[9],
+        score: i32::from_le_bytes(bytes[10..14].try_into().expect("four bytes")),
+        nodes: u64::from_le_bytes(bytes[14..22].try_into().expect("eight bytes")),
+        pv: bytes[PUT_HEAD_LEN..body]
+            .chunks_exact(2)
+            .map(|raw| Move::from_raw(u16::from_le_bytes([raw[0], raw[1]])))
+            .collect(),
+    });
+    Decoded::Record((key, entry), body + 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(depth: u8, score: i32) -> CachedAnalysis {
+        CachedAnalysis { depth, score, pv: vec![Move::from_iccs("h2e2").unwrap()], nodes: 1000 }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("chessai-cache-{name}-{}.bin", std::process::id()))
+    }
+
+    #[test]
+    fn entries_survive_reopen_and_keep_the_deepest() {
+        let path = temp_path("reopen");
+        {
+            let mut cache = AnalysisCache::open(&path, 16).unwrap();
+            cache.insert(1, entry(6, 10)).unwrap();
+            cache.insert(1, entry(4, 99)).unwrap();
+            cache.insert(2, entry(3, -5)).unwrap();
+            cache.insert(2, entry(5, -7)).unwrap();
+            cache.remove(3).unwrap();
+        }
+        let mut cache = AnalysisCache::open(&path, 16).unwrap();
+        assert_eq!(cache.get(1), Some(&entry(6, 10)));
+        assert_eq!(cache.get(2), Some(&entry(5, -7)));
+        assert_eq!(cache.stale_records(), 1);
+        cache.compact().unwrap();
+        assert_eq!(cache.stale_records(), 0);
+        drop(cache);
+        let mut cache = AnalysisCache::open(&path, 16).unwrap();
+        assert_eq!((cache.len(), cache.get(2)), (2, Some(&entry(5, -7))));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn least_recently_used_entry_is_evicted() {
+        let path = temp_path("evict");
+        let mut cache = AnalysisCache::open(&path, 2).unwrap();
+        cache.insert(1, entry(1, 1)).unwrap();
+        cache.insert(2, entry(1, 2)).unwrap();
+        cache.get(1);
+        cache.insert(3, entry(1, 3)).unwrap();
+        assert!(cache.get(2).is_none());
+        drop(cache);
+        let mut cache = AnalysisCache::open(&path, 2).unwrap();
+        assert!(cache.get(1).is_some() && cache.get(3).is_some() && cache.get(2).is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn torn_tail_is_dropped() {
+        let path = temp_path("torn");
+        let mut cache = AnalysisCache::open(&path, 8).unwrap();
+        cache.insert(1, entry(2, 2)).unwrap();
+        cache.insert(2, entry(2, 2)).unwrap();
+        drop(cache);
+        let len = std::fs::metadata(&path).unwrap().len();
+        OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 3).unwrap();
+
+        let mut cache = AnalysisCache::open(&path, 8).unwrap();
+        assert_eq!(cache.len(), 1);
+        cache.insert(3, entry(2, 2)).unwrap();
+        drop(cache);
+        assert_eq!(AnalysisCache::open(&path, 8).unwrap().len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn damaged_record_mid_file_is_an_error() {
+        let path = temp_path("damaged");
+        let mut cache = AnalysisCache::open(&path, 8).unwrap();
+        cache.insert(1, entry(2, 2)).unwrap();
+        cache.insert(2, entry(2, 2)).unwrap();
+        drop(cache);
+        let bytes = std::fs::read(&path).unwrap();
+        // The score, then the PV length: a longer PV would run past the end of the file
+        // and must not pass for a torn tail.
+        for offset in [12, 22] {
+            let mut damaged = bytes.clone();
+            damaged[HEADER_LEN + offset] ^= 0x40;
+            std::fs::write(&path, &damaged).unwrap();
+
+            let err = AnalysisCache::open(&path, 8).err().expect("must be rejected");
+            assert!(matches!(err, ChessAIError::BadCacheFile(m) if m.contains("damaged")), "offset {offset}");
+            assert_eq!(std::fs::metadata(&path).unwrap().len(), bytes.len() as u64, "nothing truncated");
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,295 @@
+//! Self-play training data. The engine plays itself at a fixed search budget from book or
+//! random openings; every searched position is recorded with its score, the move played
+//! and, once the game is over, the result.
+//!
+//! Records are text lines `<fen> | <score> | <move> | <result>`, score and result from
+//! red's point of view, so they load directly as [`TuningPosition`](crate::TuningPosition)s.
+//!
+//! ```no_run
+//! use chessai::{DataGenerator, Limits};
+//!
+//! let out = std::io::BufWriter::new(std::fs::File::create("selfplay.txt").unwrap());
+//! let generator = DataGenerator::new().games(10_000).limits(Limits::new().nodes(5000)).threads(8);
+//! let written = generator.write_to(out).unwrap();
+//! println!("{written} positions");
+//! ```
+
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::color::Color;
+use crate::engine::Engine;
+use crate::error::ChessAIError;
+use crate::fen::STARTING_FEN;
+use crate::limits::Limits;
+use crate::mv::Move;
+use crate::position::Position;
+use crate::selfplay::play_game;
+use crate::selfplay::random_opening;
+use crate::util::SplitMix64;
+
+/// How each self-play game leaves the starting position.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Opening {
+    /// Follow the embedded opening book for as long as it has moves; positions still in
+    /// book are not recorded.
+    Book,
+    /// Play this many uniformly random legal moves first.
+    RandomPlies(u8),
+}
+
+/// One recorded position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataRecord {
+    pub fen: String,
+    /// Search score, red's point of view.
+    pub score: i32,
+    pub best_move: Move,
+    /// Final game result from red's point of view: `1.0`, `0.5` or `0.0`.
+    pub result: f64,
+}
+
+impl fmt::Display for DataRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = match self.result {
+            r if r > 0.5 => "1-0",
+            r if r < 0.5 => "0-1",
+            _ => "1/2-1/2",
+        };
+        write!(f, "{} | {} | {} | {result}", self.fen, self.score, self.best_move.to_iccs())
+    }
+}
+
+impl FromStr for DataRecord {
+    type Err = ChessAIError;
+
+    fn from_str(line: &str) -> Result<DataRecord, ChessAIError> {
+        let bad = || ChessAIError::BadDataRecord(line.to_string());
+        let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+        let [fen, score, best_move, result] = fields[..] else { return Err(bad()) };
+        Position::from_fen(fen).map_err(|_| bad())?;
+        let result = match result {
+            "1-0" => 1.0,
+            "0-1" => 0.0,
+            "1/2-1/2" => 0.5,
+            _ => return Err(bad()),
+        };
+        Ok(DataRecord {
+            fen: fen.to_string(),
+            score: score.parse().map_err(|_| bad())?,
+            best_move: Move::from_iccs(best_move).map_err(|_| bad())?,
+            result,
+        })
+    }
+}
+
+/// Self-play data generator.
+#[derive(Clone, Debug)]
+pub struct DataGenerator {
+    games: u32,
+    limits: Limits,
+    opening: Opening,
+    skip_in_check: bool,
+    skip_captures: bool,
+    threads: usize,
+    hash_mb: usize,
+    seed: u64,
+}
+
+impl Default for DataGenerator {
+    fn default() -> Self { Self::new() }
+}
+
+impl DataGenerator {
+    /// 100 games at 5000 nodes a move from 8 random plies, skipping positions in check
+    /// and positions whose best move is a capture.
+    pub fn new() -> Self {
+        DataGenerator {
+            games: 100,
+            limits: Limits::new().nodes(5000),
+            opening: Opening::RandomPlies(8),
+            skip_in_check: true,
+            skip_captures: true,
+            threads: 1,
+            hash_mb: 16,
+            seed: 0x4441_5441,
+        }
+    }
+
+    #[must_use]
+    pub fn games(mut self, n: u32) -> Self {
+        self.games = n;
+        self
+    }
+
+    /// Search limits of every move.
+    #[must_use]
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    #[must_use]
+    pub fn opening(mut self, opening: Opening) -> Self {
+        self.opening = opening;
+        self
+    }
+
+    /// Drop positions where the side to move is in check.
+    #[must_use]
+    pub fn skip_in_check(mut self, yes: bool) -> Self {
+        self.skip_in_check = yes;
+        self
+    }
+
+    /// Drop positions whose best move is a capture: their static evaluation says little
+    /// about the search score.
+    #[must_use]
+    pub fn skip_captures(mut self, yes: bool) -> Self {
+        self.skip_captures = yes;
+        self
+    }
+
+    /// Games played at once.
+    #[must_use]
+    pub fn threads(mut self, n: usize) -> Self {
+        self.threads = n.max(1);
+        self
+    }
+
+    /// Hash size of each engine, in megabytes.
+    #[must_use]
+    pub fn hash_size(mut self, mb: usize) -> Self {
+        self.hash_mb = mb;
+        self
+    }
+
+    /// Seed of the openings; game `i` uses `seed + i`, whatever thread plays it.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Play the games, handing each finished game's records to `sink` as it completes.
+    /// Returns the number of records produced.
+    pub fn run(&self, mut sink: impl FnMut(&[DataRecord])) -> u64 {
+        let next = AtomicUsize::new(0);
+        let mut produced = 0;
+        thread::scope(|scope| {
+            let (tx, rx) = mpsc::channel::<Vec<DataRecord>>();
+            for _ in 0..self.threads.min(self.games as usize) {
+                let tx = tx.clone();
+                let next = &next;
+                scope.spawn(move || {
+                    loop {
+                        let game = next.fetch_add(1, Ordering::Relaxed);
+                        if game >= self.games as usize || tx.send(self.play(game as u64)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(tx);
+            for records in rx {
+                produced += records.len() as u64;
+                sink(&records);
+            }
+        });
+        produced
+    }
+
+    /// [`run`](Self::run), writing one text line per record to `out`.
+    pub fn write_to(&self, mut out: impl Write) -> Result<u64, ChessAIError> {
+        let mut failure = None;
+        let produced = self.run(|records| {
+            for record in records {
+                if failure.is_none()
+                    && let Err(err) = writeln!(out, "{record}")
+                {
+                    failure = Some(err);
+                }
+            }
+        });
+        match failure {
+            Some(err) => Err(err.into()),
+            None => {
+                out.flush()?;
+                Ok(produced)
+            }
+        }
+    }
+
+    fn play(&self, game: u64) -> Vec<DataRecord> {
+        let seed = self.seed.wrapping_add(game);
+        let engine =
+            || Engine::builder().hash_size(self.hash_mb).use_book(self.opening == Opening::Book).seed(seed).build();
+        let (mut red, mut black) = (engine(), engine());
+        let opening = match self.opening {
+            Opening::Book => Vec::new(),
+            Opening::RandomPlies(plies) => random_opening(&mut SplitMix64::new(seed), plies),
+        };
+        let mut records = Vec::new();
+        let status = play_game(&mut red, &mut black, STARTING_FEN, &opening, self.limits, |engine, info, mv| {
+            let pos = engine.position();
+            let us = pos.side_to_move();
+            if info.depth == 0
+                || (self.skip_in_check && pos.is_in_check(us))
+                || (self.skip_captures && pos.piece_at(mv.dst()).is_some())
+            {
+                return;
+            }
+            let score = if us == Color::Red { info.score } else { -info.score };
+            records.push(DataRecord { fen: pos.to_fen(), score, best_move: mv, result: 0.5 });
+        })
+        .expect("startpos FEN parses");
+        let result = status.red_score().unwrap_or(0.5);
+        for record in &mut records {
+            record.result = result;
+        }
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tune::TuningPosition;
+
+    #[test]
+    fn records_filtered_positions_with_the_game_result() {
+        let generator = DataGenerator::new().games(2).limits(Limits::new().depth(1)).threads(2).hash_size(1).seed(9);
+        let mut out = Vec::new();
+        let written = generator.write_to(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count() as u64, written);
+        assert!(written > 0);
+
+        for line in text.lines() {
+            let record: DataRecord = line.parse().unwrap();
+            assert_eq!(record.to_string(), line);
+            let pos = Position::from_fen(&record.fen).unwrap();
+            assert!(pos.is_legal(record.best_move));
+            assert!(!pos.is_in_check(pos.side_to_move()));
+            assert!(pos.piece_at(record.best_move.dst()).is_none());
+            let tuning: TuningPosition = line.parse().unwrap();
+            assert_eq!(tuning.result(), record.result);
+        }
+
+        // Same seeds, same games.
+        let mut again = Vec::new();
+        generator.write_to(&mut again).unwrap();
+        let sorted = |bytes: &[u8]| {
+            let mut lines: Vec<String> = String::from_utf8(bytes.to_vec()).unwrap().lines().map(String::from).collect();
+            lines.sort();
+            lines
+        };
+        assert_eq!(sorted(text.as_bytes()), sorted(&again));
+        assert!("bad | 0 | a0a1 | 1-0".parse::<DataRecord>().is_err());
+    }
+}
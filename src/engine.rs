@@ -13,33 +13,106 @@
 //! println!("{:?} score={} depth={} nps={}", info.best_move, info.score, info.depth, info.nps);
 //! ```
 
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::thread;
 
 use crate::book::Book;
+use crate::cache::AnalysisCache;
+use crate::cache::CachedAnalysis;
+use crate::color::Color;
 use crate::error::ChessAIError;
+use crate::eval::EvalParams;
+use crate::eval::MATE_VALUE;
 use crate::fen::STARTING_FEN;
 use crate::limits::Limits;
+use crate::limits::MAX_SEARCH_DEPTH;
+use crate::mate::MateOutcome;
 use crate::movegen::MoveList;
-use crate::movegen::generate_pseudo;
+use crate::movegen::generate_legal;
 use crate::mv::Move;
 use crate::position::Position;
 use crate::position::UndoInfo;
+use crate::search::DEFAULT_QSEARCH_CHECKS;
+use crate::search::MOVE_LIMIT_PLIES;
+use crate::search::RootLine;
 use crate::search::Search;
 use crate::search::SearchInfo;
+use crate::search::SearchParams;
+use crate::search::mate_in;
+use crate::skill::Skill;
+use crate::tablebase::Tablebases;
 use crate::tt::TranspositionTable;
+use crate::util::Fnv64;
 use crate::util::SplitMix64;
 
-/// Fixed seed for the internal book-randomisation RNG. A deterministic seed is fine: the
-/// book picks between moves stochastically, but users don't need to tune the sequence.
+/// Default seed for the engine RNG (book choices, skill-level noise). Override it with
+/// [`EngineBuilder::seed`] to vary or pin down the sequence.
 const BOOK_RNG_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
 
+/// Node budget of a [deterministic](EngineBuilder::deterministic) search whose limits set
+/// neither a depth nor a node count — about what the default one-second limit searches.
+pub const DETERMINISTIC_NODES: u64 = 1_000_000;
+
+/// `limits` without the time bound, which would make results depend on the machine, and
+/// with the [`DETERMINISTIC_NODES`] budget when nothing else bounds the search.
+fn deterministic_limits(mut limits: Limits) -> Limits {
+    limits.max_time = None;
+    if limits.max_nodes.is_none() && limits.max_depth >= MAX_SEARCH_DEPTH {
+        limits.max_nodes = Some(DETERMINISTIC_NODES);
+    }
+    limits
+}
+
+/// Whether the game played on an [`Engine`] is over, and how.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GameStatus {
+    Ongoing,
+    /// The side to move has no legal move and has lost: checkmate or, in xiangqi, stalemate.
+    Mate {
+        winner: Color,
+    },
+    /// The current position has occurred for the third time. Always a draw here; the
+    /// search already avoids the repetitions xiangqi forfeits (perpetual check or chase).
+    Repetition,
+    /// 60 moves by each side without a capture.
+    MoveLimit,
+}
+
+impl GameStatus {
+    pub fn is_over(self) -> bool { self != GameStatus::Ongoing }
+
+    /// Result from red's point of view: `1.0` red won, `0.5` draw, `0.0` black won;
+    /// `None` while the game is still going.
+    pub fn red_score(self) -> Option<f64> {
+        match self {
+            GameStatus::Ongoing => None,
+            GameStatus::Mate { winner: Color::Red } => Some(1.0),
+            GameStatus::Mate { winner: Color::Black } => Some(0.0),
+            GameStatus::Repetition | GameStatus::MoveLimit => Some(0.5),
+        }
+    }
+}
+
 pub struct EngineBuilder {
     hash_size_bytes: usize,
     use_book: bool,
     threads: u8,
+    eval_params: Arc<EvalParams>,
+    search_params: Arc<SearchParams>,
+    contempt: Option<i32>,
+    skill: Option<Skill>,
+    seed: u64,
+    deterministic: bool,
+    qsearch_checks: u8,
+    large_pages: bool,
+    analysis_cache: Option<AnalysisCache>,
+    tablebases: Option<Arc<Tablebases>>,
 }
 
 impl Default for EngineBuilder {
@@ -48,6 +121,16 @@ impl Default for EngineBuilder {
             hash_size_bytes: 32 * 1024 * 1024, // 32 MB default
             use_book: true,
             threads: 1,
+            eval_params: Arc::new(EvalParams::default()),
+            search_params: Arc::new(SearchParams::default()),
+            contempt: None,
+            skill: None,
+            seed: BOOK_RNG_SEED,
+            deterministic: false,
+            qsearch_checks: DEFAULT_QSEARCH_CHECKS,
+            large_pages: false,
+            analysis_cache: None,
+            tablebases: None,
         }
     }
 }
@@ -74,23 +157,137 @@ impl EngineBuilder {
         self
     }
 
+    /// Evaluation tables and constants (defaults to the compile-time tables).
+    #[must_use]
+    pub fn eval_params(mut self, params: EvalParams) -> Self {
+        self.eval_params = Arc::new(params);
+        self
+    }
+
+    /// Search pruning and reduction constants (defaults to the hand-set values). Values
+    /// outside a parameter's supported range are clamped into it.
+    #[must_use]
+    pub fn search_params(mut self, params: SearchParams) -> Self {
+        self.search_params = Arc::new(params.clamped());
+        self
+    }
+
+    /// Contempt in centipawns, from the searching side's perspective: a draw (repetition or
+    /// the natural move limit) scores `-contempt` for the engine. Defaults to the eval
+    /// params' `draw_value`; [`Limits::contempt`] overrides it per search.
+    #[must_use]
+    pub fn contempt(mut self, cp: i32) -> Self {
+        self.contempt = Some(cp);
+        self
+    }
+
+    /// Playing strength from 0 (beginner) to 20 (full strength, the default). Lower levels
+    /// cap depth and nodes, search a few MultiPV lines single-threaded and pick among them
+    /// with seeded noise, occasionally playing a move that drops material. Values above 20
+    /// are clamped.
+    #[must_use]
+    pub fn skill_level(mut self, level: u8) -> Self {
+        self.skill = Skill::new(level);
+        self
+    }
+
+    /// Seed for the engine RNG that drives book choices and skill-level noise.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Reproducible searches: one thread, time limits ignored (bound searches with
+    /// [`Limits::depth`] / [`Limits::nodes`]; a search given neither stops after
+    /// [`DETERMINISTIC_NODES`] nodes), a cleared hash table before every search, and the RNG
+    /// re-derived from the seed and the position so book and skill choices depend only on
+    /// what is on the board.
+    #[must_use]
+    pub fn deterministic(mut self, yes: bool) -> Self {
+        self.deterministic = yes;
+        self
+    }
+
+    /// How many quiescence plies also search quiet checking moves (default 1). Higher
+    /// values see deeper cannon / knight mating nets at the horizon at some cost in
+    /// nodes; `0` restricts the qsearch to captures and evasions.
+    #[must_use]
+    pub fn qsearch_checks(mut self, plies: u8) -> Self {
+        self.qsearch_checks = plies;
+        self
+    }
+
+    /// Back the transposition table with huge pages where the OS offers them (transparent
+    /// huge pages on Linux); silently falls back to normal pages elsewhere.
+    #[must_use]
+    pub fn large_pages(mut self, yes: bool) -> Self {
+        self.large_pages = yes;
+        self
+    }
+
+    /// Consult `cache` before searching and record finished searches in it. See
+    /// [`Engine::search_with`] for when the cache applies.
+    #[must_use]
+    pub fn analysis_cache(mut self, cache: AnalysisCache) -> Self {
+        self.analysis_cache = Some(cache);
+        self
+    }
+
+    /// Probe `tablebases` at the root and inside the search.
+    #[must_use]
+    pub fn tablebases(mut self, tablebases: Tablebases) -> Self {
+        self.tablebases = Some(Arc::new(tablebases));
+        self
+    }
+
     pub fn build(self) -> Engine {
-        let position = Position::from_fen(STARTING_FEN).expect("startpos FEN parses");
+        let mut position = Position::from_fen(STARTING_FEN).expect("startpos FEN parses");
+        position.set_eval_params(Arc::clone(&self.eval_params));
         let book = if self.use_book { Some(Book::embedded()) } else { None };
         Engine {
             position,
-            tt: Arc::new(TranspositionTable::new(self.hash_size_bytes)),
+            tt: Arc::new(TranspositionTable::allocate(self.hash_size_bytes, self.large_pages)),
             book,
             stop: Arc::new(AtomicBool::new(false)),
-            rng: SplitMix64::new(BOOK_RNG_SEED),
+            rng: SplitMix64::new(self.seed),
             move_counter: 0,
             game_keys: Vec::with_capacity(256),
             move_history: Vec::with_capacity(256),
             threads: self.threads,
+            eval_params: self.eval_params,
+            search_params: self.search_params,
+            contempt: self.contempt,
+            skill: self.skill,
+            seed: self.seed,
+            deterministic: self.deterministic,
+            qsearch_checks: self.qsearch_checks,
+            analysis_cache: self.analysis_cache,
+            tablebases: self.tablebases,
         }
     }
 }
 
+/// Engine settings copied into every [`Search`], owned so that worker threads can take
+/// their own copy.
+struct SearchSetup {
+    game_keys: Vec<u64>,
+    /// One flag per played move: was it a capture? Aligned with `game_keys`.
+    captures: Vec<bool>,
+    qsearch_checks: u8,
+    tablebases: Option<Arc<Tablebases>>,
+    params: Arc<SearchParams>,
+}
+
+impl SearchSetup {
+    fn apply(self, search: &mut Search) {
+        search.seed_game_history(&self.game_keys, self.captures);
+        search.qsearch_checks = self.qsearch_checks;
+        search.tablebases = self.tablebases;
+        search.params = self.params;
+    }
+}
+
 pub struct Engine {
     position: Position,
     tt: Arc<TranspositionTable>,
@@ -101,6 +298,15 @@ pub struct Engine {
     game_keys: Vec<u64>,
     move_history: Vec<(Move, UndoInfo)>,
     threads: u8,
+    eval_params: Arc<EvalParams>,
+    search_params: Arc<SearchParams>,
+    contempt: Option<i32>,
+    skill: Option<Skill>,
+    seed: u64,
+    deterministic: bool,
+    qsearch_checks: u8,
+    analysis_cache: Option<AnalysisCache>,
+    tablebases: Option<Arc<Tablebases>>,
 }
 
 impl Engine {
@@ -112,6 +318,16 @@ impl Engine {
 
     pub fn set_fen(&mut self, fen: &str) -> Result<(), ChessAIError> {
         self.position = Position::from_fen(fen)?;
+        self.position.set_eval_params(Arc::clone(&self.eval_params));
+        self.clear_hash();
+        self.move_counter = 0;
+        self.game_keys.clear();
+        self.move_history.clear();
+        Ok(())
+    }
+
+    /// Empty the transposition table, keeping its size.
+    pub fn clear_hash(&mut self) {
         // Shared TT — need interior-mutable clear. `Arc::get_mut` works when we're the sole
         // owner, which is true here since workers are joined before returning.
         if let Some(tt) = Arc::get_mut(&mut self.tt) {
@@ -119,50 +335,46 @@ impl Engine {
         } else {
             // Rare: someone still holds a reference. Allocate a fresh table; old one is
             // dropped when its last clone goes away.
-            self.tt = Arc::new(TranspositionTable::new(self.tt.size_bytes()));
+            self.tt = Arc::new(TranspositionTable::allocate(self.tt.size_bytes(), self.tt.large_pages()));
         }
-        self.move_counter = 0;
-        self.game_keys.clear();
-        self.move_history.clear();
-        Ok(())
     }
 
+    /// Replace the transposition table with an empty one of `mb` megabytes (rounded down
+    /// to a power of two). Position, history, book and RNG state are kept.
+    pub fn set_hash_size(&mut self, mb: usize) {
+        let large_pages = self.tt.large_pages();
+        // Drop the old table first so the two never coexist in memory.
+        self.tt = Arc::new(TranspositionTable::new(0));
+        self.tt = Arc::new(TranspositionTable::allocate(mb * 1024 * 1024, large_pages));
+    }
+
+    /// Current transposition-table size in bytes.
+    pub fn hash_size_bytes(&self) -> usize { self.tt.size_bytes() }
+
     pub fn reset_to_startpos(&mut self) { self.set_fen(STARTING_FEN).expect("startpos FEN must parse"); }
 
     pub fn fen(&self) -> String { self.position.to_fen() }
 
-    pub fn side_to_move(&self) -> crate::color::Color { self.position.side_to_move() }
+    pub fn side_to_move(&self) -> Color { self.position.side_to_move() }
 
     pub fn threads(&self) -> u8 { self.threads }
 
+    pub fn eval_params(&self) -> &EvalParams { &self.eval_params }
+
+    pub fn search_params(&self) -> &SearchParams { &self.search_params }
+
     pub fn legal_moves(&mut self) -> Vec<Move> {
-        let mut pseudo = MoveList::new();
-        generate_pseudo(&self.position, &mut pseudo);
-        let mut legal = Vec::with_capacity(pseudo.len());
-        let us = self.position.side_to_move();
-        for mv in pseudo.as_slice() {
-            let undo = self.position.make_move(*mv);
-            if !self.position.is_in_check(us) {
-                legal.push(*mv);
-            }
-            self.position.undo_move(*mv, undo);
-        }
-        legal
+        let mut legal = MoveList::new();
+        generate_legal(&self.position, &mut legal);
+        legal.as_slice().to_vec()
     }
 
     pub fn make_move(&mut self, mv: Move) -> bool {
-        let mut pseudo = MoveList::new();
-        generate_pseudo(&self.position, &mut pseudo);
-        if !pseudo.as_slice().contains(&mv) {
+        if !self.position.is_legal(mv) {
             return false;
         }
-        let us = self.position.side_to_move();
         let pre_key = self.position.zobrist_key();
         let undo = self.position.make_move(mv);
-        if self.position.is_in_check(us) {
-            self.position.undo_move(mv, undo);
-            return false;
-        }
         self.game_keys.push(pre_key);
         self.move_counter += 1;
         self.move_history.push((mv, undo));
@@ -184,29 +396,54 @@ impl Engine {
 
     pub fn game_key_history(&self) -> &[u64] { &self.game_keys }
 
+    /// Adjudicate the game played since the last `set_fen`: mate, threefold repetition, or
+    /// the natural move limit.
+    pub fn game_status(&self) -> GameStatus {
+        let mut legal = MoveList::new();
+        generate_legal(&self.position, &mut legal);
+        if legal.len() == 0 {
+            return GameStatus::Mate { winner: self.position.side_to_move().flip() };
+        }
+        let key = self.position.zobrist_key();
+        if self.game_keys.iter().filter(|&&k| k == key).count() >= 2 {
+            return GameStatus::Repetition;
+        }
+        let quiet_plies = self.move_history.iter().rev().take_while(|(_, undo)| undo.captured.is_none()).count();
+        if quiet_plies >= MOVE_LIMIT_PLIES as usize {
+            return GameStatus::MoveLimit;
+        }
+        GameStatus::Ongoing
+    }
+
     pub fn history_len(&self) -> usize { self.move_history.len() }
 
     pub fn move_history(&self) -> impl Iterator<Item = Move> + '_ { self.move_history.iter().map(|(m, _)| *m) }
 
+    // ---------------- Hash persistence ----------------
+
+    /// Write the transposition table, including its age counter, to `path`.
+    pub fn save_hash(&self, path: impl AsRef<Path>) -> Result<(), ChessAIError> {
+        self.tt.write_to(BufWriter::new(File::create(path)?))
+    }
+
+    /// Replace the transposition table with one saved by [`save_hash`](Self::save_hash). The
+    /// table takes the file's size. Files saved by a build with a different Zobrist layout or
+    /// entry packing are rejected and leave the current table untouched. Since
+    /// [`set_fen`](Self::set_fen) clears the table, load after setting up the position.
+    pub fn load_hash(&mut self, path: impl AsRef<Path>) -> Result<(), ChessAIError> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let tt = TranspositionTable::read_from(BufReader::new(file), len, self.tt.large_pages())?;
+        self.tt = Arc::new(tt);
+        Ok(())
+    }
+
     // ---------------- Book ----------------
 
     pub fn book_move(&mut self) -> Option<Move> {
         let book = self.book.as_ref()?;
         let mv = book.probe(&self.position, &mut self.rng)?;
-        let mut ml = MoveList::new();
-        generate_pseudo(&self.position, &mut ml);
-        let mut legal = false;
-        for m in ml.as_slice() {
-            if *m == mv {
-                let undo = self.position.make_move(*m);
-                if !self.position.is_in_check(self.position.side_to_move().flip()) {
-                    legal = true;
-                }
-                self.position.undo_move(*m, undo);
-                break;
-            }
-        }
-        if legal { Some(mv) } else { None }
+        if self.position.is_legal(mv) { Some(mv) } else { None }
     }
 
     // ---------------- Search ----------------
@@ -215,10 +452,31 @@ impl Engine {
 
     pub fn search(&mut self, limits: Limits) -> SearchInfo { self.search_with(limits, |_| {}) }
 
+    /// Search the current position, calling `callback` after every completed iteration.
+    ///
+    /// With an [analysis cache](EngineBuilder::analysis_cache), a full-strength single-PV
+    /// search that is not [deterministic](EngineBuilder::deterministic) is answered from
+    /// the cache when it holds an entry for this position that covers `limits`: at least as
+    /// deep as a depth limit, at least as many nodes as a node limit, or any entry for a
+    /// search bounded only by time (the cache keeps the deepest result it has seen). Every
+    /// such search is recorded afterwards. Entries are keyed by the position together with
+    /// the contempt, evaluation and search parameters, ignoring game history; write-back
+    /// errors are ignored, leaving the entry in memory only.
     pub fn search_with<F>(&mut self, limits: Limits, mut callback: F) -> SearchInfo
     where
         F: FnMut(&SearchInfo),
     {
+        let mut limits = limits;
+        if self.deterministic {
+            self.rng = SplitMix64::new(self.seed ^ self.position.zobrist_key());
+            limits = deterministic_limits(limits);
+            self.clear_hash();
+        }
+
+        if let Some(moves) = limits.mate {
+            return self.search_mate(moves, limits, &mut callback);
+        }
+
         // Book first (disabled if `use_book(false)` was set).
         if self.book.is_some()
             && let Some(mv) = self.book_move()
@@ -231,6 +489,7 @@ impl Engine {
                 nodes: 0,
                 time: std::time::Duration::ZERO,
                 nps: 0,
+                ..Default::default()
             };
             callback(&info);
             return info;
@@ -238,18 +497,184 @@ impl Engine {
 
         self.stop.store(false, Ordering::Relaxed);
 
-        if self.threads <= 1 {
-            return self.search_single(limits, &mut callback);
+        if limits.contempt.is_none() {
+            limits.contempt = self.contempt;
+        }
+        if let Some(skill) = self.skill {
+            return self.search_weakened(skill, limits, &mut callback);
+        }
+
+        let cacheable = self.analysis_cache.is_some() && limits.multipv <= 1 && !self.deterministic;
+        let cache_key = self.cache_key(limits.contempt);
+        if cacheable && let Some(info) = self.cached_analysis(cache_key, &limits) {
+            callback(&info);
+            return info;
         }
-        self.search_parallel(limits, &mut callback)
+        let info = if self.threads <= 1 || self.deterministic {
+            self.search_single(limits, &mut callback)
+        } else {
+            self.search_parallel(limits, &mut callback)
+        };
+        if cacheable
+            && info.depth > 0
+            && let Some(cache) = self.analysis_cache.as_mut()
+        {
+            let entry = CachedAnalysis { depth: info.depth, score: info.score, pv: info.pv.clone(), nodes: info.nodes };
+            let _ = cache.insert(cache_key, entry);
+        }
+        info
+    }
+
+    /// The analysis cache, e.g. to [`compact`](AnalysisCache::compact) it.
+    pub fn analysis_cache_mut(&mut self) -> Option<&mut AnalysisCache> { self.analysis_cache.as_mut() }
+
+    /// Analysis cache key: the position's Zobrist key mixed with every setting that
+    /// changes a search's result, so engines configured differently never share entries.
+    fn cache_key(&self, contempt: Option<i32>) -> u64 {
+        let mut h = Fnv64::new();
+        h.update(self.eval_params.to_string().as_bytes());
+        h.update(self.search_params.to_string().as_bytes());
+        h.update(&contempt.map_or([0; 5], |cp| {
+            let mut bytes = [1; 5];
+            bytes[1..].copy_from_slice(&cp.to_le_bytes());
+            bytes
+        }));
+        h.update(&[self.qsearch_checks, u8::from(self.tablebases.is_some())]);
+        self.position.zobrist_key() ^ h.finish()
+    }
+
+    /// The cached result under `key` if it covers `limits` (see [`search_with`](Self::search_with)),
+    /// provided its best move is legal here (guarding against key collisions).
+    fn cached_analysis(&mut self, key: u64, limits: &Limits) -> Option<SearchInfo> {
+        let entry = self.analysis_cache.as_mut()?.get(key)?;
+        let covers = match limits.max_nodes {
+            _ if limits.max_depth < MAX_SEARCH_DEPTH => entry.depth >= limits.max_depth,
+            Some(nodes) => entry.nodes >= nodes,
+            None => true,
+        };
+        let entry = covers.then(|| entry.clone())?;
+        let best_move = entry.pv.first().copied().filter(|&mv| self.position.is_legal(mv))?;
+        Some(SearchInfo {
+            depth: entry.depth,
+            score: entry.score,
+            best_move: Some(best_move),
+            lines: vec![RootLine { score: entry.score, pv: entry.pv.clone() }],
+            mate_in: mate_in(entry.score),
+            pv: entry.pv,
+            nodes: entry.nodes,
+            ..Default::default()
+        })
+    }
+
+    /// [`Limits::mate`] search: the proof-number solver in place of alpha-beta. A found mate
+    /// is reported like a normal search result, with the mate score and the forced line.
+    fn search_mate<F: FnMut(&SearchInfo)>(&mut self, moves: u8, limits: Limits, callback: &mut F) -> SearchInfo {
+        self.stop.store(false, Ordering::Relaxed);
+        let start = std::time::Instant::now();
+        let (outcome, nodes) = crate::mate::solve(&mut self.position, moves, &limits, &self.stop);
+        let time = start.elapsed();
+        let nps = if time.as_micros() > 0 { (nodes as f64 / time.as_secs_f64()) as u64 } else { 0 };
+        let mut info = SearchInfo { nodes, time, nps, ..Default::default() };
+        if let MateOutcome::Mate(line) = &outcome {
+            info.depth = line.len() as u8;
+            info.score = MATE_VALUE - line.len() as i32;
+            info.mate_in = Some(line.len().div_ceil(2) as i32);
+            info.best_move = line.first().copied();
+            info.pv = line.clone();
+            info.lines = vec![RootLine { score: info.score, pv: line.clone() }];
+        }
+        info.mate = Some(outcome);
+        callback(&info);
+        info
+    }
+
+    /// The engine settings a new [`Search`] starts from.
+    fn search_setup(&self) -> SearchSetup {
+        SearchSetup {
+            game_keys: self.game_keys.clone(),
+            captures: self.move_history.iter().map(|(_, undo)| undo.captured.is_some()).collect(),
+            qsearch_checks: self.qsearch_checks,
+            tablebases: self.tablebases.clone(),
+            params: Arc::clone(&self.search_params),
+        }
+    }
+
+    /// Skill-limited search: a capped single-threaded MultiPV search, then a noisy pick
+    /// among its lines or, now and then, a blunder. A blunder outside those lines is scored
+    /// by a second search restricted to it. The callback sees the raw iterations; the
+    /// returned info carries the move actually chosen.
+    fn search_weakened<F: FnMut(&SearchInfo)>(&mut self, skill: Skill, limits: Limits, callback: &mut F) -> SearchInfo {
+        let limits = skill.limits(limits);
+        let mut info = self.search_single(limits, callback);
+        if info.lines.first().is_none_or(|line| line.pv.is_empty()) {
+            return info;
+        }
+        let legal = self.legal_moves();
+        let line = match skill.blunder(&self.position, &legal, &mut self.rng) {
+            Some(mv) => match info.lines.iter().find(|line| line.pv.first() == Some(&mv)) {
+                Some(line) => Some(line.clone()),
+                None => {
+                    let scored = self.search_root_moves(limits.multipv(1), &[mv]);
+                    info.nodes += scored.nodes;
+                    Some(RootLine { score: scored.score, pv: scored.pv })
+                }
+            },
+            None => skill.pick(&info, &mut self.rng),
+        };
+        if let Some(line) = line {
+            info.best_move = line.pv.first().copied();
+            info.score = line.score;
+            info.pv = line.pv;
+        }
+        info
     }
 
     fn search_single<F: FnMut(&SearchInfo)>(&mut self, limits: Limits, callback: &mut F) -> SearchInfo {
+        let setup = self.search_setup();
         let mut search = Search::new(&mut self.position, Arc::clone(&self.tt), Arc::clone(&self.stop));
-        search.seed_game_history(&self.game_keys);
+        setup.apply(&mut search);
         search.run(limits, |info| callback(info))
     }
 
+    /// Single-threaded search of the current position that only considers `root_moves`
+    /// at the root (all moves when empty). Skips the book, cache and skill settings.
+    pub(crate) fn search_root_moves(&mut self, limits: Limits, root_moves: &[Move]) -> SearchInfo {
+        let mut limits = limits;
+        if limits.contempt.is_none() {
+            limits.contempt = self.contempt;
+        }
+        self.stop.store(false, Ordering::Relaxed);
+        let setup = self.search_setup();
+        let mut search = Search::new(&mut self.position, Arc::clone(&self.tt), Arc::clone(&self.stop));
+        setup.apply(&mut search);
+        search.root_moves = root_moves.to_vec();
+        search.run(limits, |_| {})
+    }
+
+    /// [`search_root_moves`](Self::search_root_moves) over all moves after passing the
+    /// turn, together with the position searched. `None` when the side to move is in
+    /// check, since passing would leave the king en prise.
+    pub(crate) fn search_after_pass(&mut self, limits: Limits) -> Option<(SearchInfo, Position)> {
+        if self.position.is_in_check(self.position.side_to_move()) {
+            return None;
+        }
+        let undo = self.position.make_null();
+        let passed = self.position.clone();
+        let info = self.search_root_moves(limits, &[]);
+        self.position.undo_null(undo);
+        Some((info, passed))
+    }
+
+    /// [`Limits::mate`] probe of the position after `mv`: whether the opponent then mates
+    /// in at most `moves` moves, within the node and time budget of `limits`, and the
+    /// nodes spent finding out.
+    pub(crate) fn mate_after(&mut self, mv: Move, moves: u8, limits: Limits) -> (MateOutcome, u64) {
+        let mut after = self.position.clone();
+        after.make_move(mv);
+        self.stop.store(false, Ordering::Relaxed);
+        crate::mate::solve(&mut after, moves, &limits, &self.stop)
+    }
+
     fn search_parallel<F: FnMut(&SearchInfo)>(&mut self, limits: Limits, callback: &mut F) -> SearchInfo {
         let n = self.threads as usize;
 
@@ -259,11 +684,11 @@ impl Engine {
             for tid in 1..n {
                 let tt = Arc::clone(&self.tt);
                 let stop = Arc::clone(&self.stop);
-                let game_keys = self.game_keys.clone();
+                let setup = self.search_setup();
                 let mut pos = self.position.clone();
                 let h = scope.spawn(move || {
                     let mut search = Search::new(&mut pos, tt, stop);
-                    search.seed_game_history(&game_keys);
+                    setup.apply(&mut search);
                     search.thread_id = tid as u8;
                     search.run(limits, |_info| {})
                 });
@@ -273,8 +698,9 @@ impl Engine {
             // Main thread (id 0). Drives the user-visible callback and owns the returned
             // info by default; workers' info is merged below.
             let main_info = {
+                let setup = self.search_setup();
                 let mut search = Search::new(&mut self.position, Arc::clone(&self.tt), Arc::clone(&self.stop));
-                search.seed_game_history(&self.game_keys);
+                setup.apply(&mut search);
                 search.thread_id = 0;
                 search.run(limits, |info| callback(info))
             };
@@ -309,6 +735,7 @@ mod tests {
     use std::time::Duration;
 
     use super::*;
+    use crate::search::ScoreBound;
 
     #[test]
     fn single_thread_plays_a_move() {
@@ -324,6 +751,153 @@ mod tests {
         assert!(info.best_move.is_some());
     }
 
+    #[test]
+    fn multipv_reports_distinct_ordered_lines() {
+        let mut e = EngineBuilder::default().use_book(false).build();
+        let info = e.search(Limits::new().depth(4).multipv(3));
+        assert_eq!(info.lines.len(), 3);
+        assert_eq!(info.best_move, info.lines[0].pv.first().copied());
+        assert!(info.lines.windows(2).all(|w| w[0].score >= w[1].score && w[0].pv[0] != w[1].pv[0]));
+    }
+
+    #[test]
+    fn skill_level_is_reproducible_per_seed() {
+        let play = |seed| {
+            let mut e = EngineBuilder::default().use_book(false).skill_level(2).seed(seed).build();
+            (0..6)
+                .map(|_| {
+                    let mv = e.search(Limits::new()).best_move.unwrap();
+                    assert!(e.make_move(mv));
+                    mv
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(play(11), play(11));
+    }
+
+    #[test]
+    fn skill_blunders_carry_their_own_score() {
+        // Any move losing material hangs the rook to a pawn; the quiet rook moves keep it.
+        const FEN: &str = "3k5/9/9/3p1p3/4R4/9/9/9/9/5K3 w";
+        let mut full = EngineBuilder::default().use_book(false).build();
+        full.set_fen(FEN).unwrap();
+        let best = full.search(Limits::new().depth(1)).score;
+        let mut blunders = 0;
+        for seed in 0..40 {
+            let mut e = EngineBuilder::default().use_book(false).skill_level(0).seed(seed).build();
+            e.set_fen(FEN).unwrap();
+            let info = e.search(Limits::new());
+            let mv = info.best_move.unwrap();
+            if e.position().see(mv) < 0 {
+                blunders += 1;
+                assert!(info.score < best - 100, "{mv} scored {} against {best}", info.score);
+                assert_eq!(info.pv.first(), Some(&mv));
+            }
+        }
+        assert!(blunders > 0);
+    }
+
+    #[test]
+    fn deterministic_searches_are_identical() {
+        let run = |threads| {
+            let mut e = EngineBuilder::default().use_book(false).threads(threads).deterministic(true).build();
+            let limits = Limits::new().nodes(20_000).time(Duration::from_millis(1));
+            let first = e.search(limits);
+            let again = e.search(limits);
+            assert_eq!((first.best_move, first.score, first.nodes), (again.best_move, again.score, again.nodes));
+            first
+        };
+        let a = run(1);
+        let b = run(4);
+        assert_eq!((a.best_move, a.score, a.nodes, a.pv), (b.best_move, b.score, b.nodes, b.pv));
+    }
+
+    #[test]
+    fn out_of_range_search_params_are_clamped() {
+        let params = SearchParams { null_move_divisor: 0, skip_size: [0; 20], ..SearchParams::default() };
+        let mut e = EngineBuilder::default().use_book(false).threads(2).search_params(params).build();
+        assert_eq!(e.search_params().null_move_divisor, 2);
+        assert!(e.search(Limits::new().depth(6)).best_move.is_some());
+    }
+
+    #[test]
+    fn deterministic_limits_stay_bounded() {
+        let default = deterministic_limits(Limits::default());
+        assert_eq!((default.max_time, default.max_nodes), (None, Some(DETERMINISTIC_NODES)));
+        assert_eq!(deterministic_limits(Limits::new()).max_nodes, Some(DETERMINISTIC_NODES));
+        assert_eq!(deterministic_limits(Limits::new().depth(6)).max_nodes, None);
+        assert_eq!(deterministic_limits(Limits::default().nodes(500)).max_nodes, Some(500));
+    }
+
+    #[test]
+    fn deterministic_book_follows_seed() {
+        let book = |seed| {
+            let mut e = EngineBuilder::default().deterministic(true).seed(seed).build();
+            (0..8).map(|_| e.search(Limits::new().depth(1)).best_move).collect::<Vec<_>>()
+        };
+        let a = book(3);
+        assert!(a.iter().all(|&mv| mv == a[0]));
+        assert_eq!(a, book(3));
+    }
+
+    #[test]
+    fn qsearch_checks_solve_mate_in_two_at_depth_two() {
+        // Mate in two where the mating move is a quiet check one ply past a depth-2 horizon.
+        const MATE_IN_TWO: [&str; 6] = [
+            "3k5/9/9/9/9/9/9/9/4C4/R3K4 w",
+            "5k3/9/9/9/9/9/9/9/3C5/3RK4 w",
+            "5k3/9/9/9/9/9/9/9/4C4/4K3R w",
+            "3k5/9/9/9/9/9/9/9/4N4/R3K4 w",
+            "5k3/9/9/9/9/9/9/9/3N5/3RK4 w",
+            "3k5/9/9/9/9/9/9/4N4/9/R3K4 w",
+        ];
+        let solved = |checks| {
+            MATE_IN_TWO
+                .iter()
+                .filter(|fen| {
+                    let mut e = EngineBuilder::default().use_book(false).qsearch_checks(checks).build();
+                    e.set_fen(fen).unwrap();
+                    e.search(Limits::new().depth(2)).score > crate::eval::WIN_VALUE
+                })
+                .count()
+        };
+        assert_eq!(solved(1), MATE_IN_TWO.len());
+        assert!(solved(0) < MATE_IN_TWO.len());
+    }
+
+    #[test]
+    fn mate_limit_reports_forced_line() {
+        let mut e = EngineBuilder::default().build();
+        e.set_fen("3k5/9/9/9/9/9/9/9/4C4/R3K4 w").unwrap();
+        let info = e.search(Limits::new().mate(3));
+        let Some(MateOutcome::Mate(line)) = &info.mate else { panic!("mate expected, got {:?}", info.mate) };
+        assert_eq!((info.pv.len(), info.score), (3, MATE_VALUE - 3));
+        assert_eq!(info.best_move, line.first().copied());
+
+        e.reset_to_startpos();
+        assert_eq!(e.search(Limits::new().mate(1)).mate, Some(MateOutcome::NoMate));
+    }
+
+    #[test]
+    fn tablebases_drive_root_and_tree() {
+        let mut tb = Tablebases::new();
+        tb.generate("KRK").unwrap();
+        let root = Position::from_fen("4k4/9/9/9/9/9/9/9/9/3K4R w").unwrap();
+        let (best, probe) = tb.best_move(&root).unwrap();
+        let mut e = EngineBuilder::default().use_book(false).tablebases(tb).build();
+
+        e.set_fen("4k4/9/9/9/9/9/9/9/9/3K4R w").unwrap();
+        let info = e.search(Limits::new().depth(1));
+        assert_eq!((info.best_move, info.score), (Some(best), MATE_VALUE - probe.dtm as i32));
+        assert_eq!(info.pv.len(), probe.dtm as usize);
+
+        // Taking the knight reaches a tabulated win that a 2-ply search cannot see alone.
+        e.set_fen("4k4/9/9/9/9/9/9/9/n8/R2K5 w").unwrap();
+        let info = e.search(Limits::new().depth(2));
+        assert_eq!(info.best_move, Some(Move::from_iccs("a0a1").unwrap()));
+        assert!(info.score > crate::eval::WIN_VALUE);
+    }
+
     #[test]
     fn legal_move_count_is_44_at_startpos() {
         let mut e = Engine::default();
@@ -405,6 +979,131 @@ mod tests {
         assert_eq!(info_a.best_move, info_b.best_move);
     }
 
+    #[test]
+    fn hash_file_roundtrip_restores_entries() {
+        let path = std::env::temp_dir().join(format!("chessai-hash-{}.bin", std::process::id()));
+        let mut e = EngineBuilder::default().use_book(false).hash_size(1).build();
+        e.search(Limits::new().depth(5));
+        e.save_hash(&path).unwrap();
+
+        let mut fresh = EngineBuilder::default().use_book(false).hash_size(4).build();
+        assert!(fresh.tt.probe(fresh.position().zobrist_key()).is_none());
+        fresh.load_hash(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(fresh.tt.size_bytes(), e.tt.size_bytes());
+        assert!(fresh.tt.probe(fresh.position().zobrist_key()).is_some());
+        assert!(matches!(fresh.load_hash(&path), Err(ChessAIError::Io(_))));
+    }
+
+    #[test]
+    fn hash_can_be_resized_and_cleared_in_place() {
+        let mut e = EngineBuilder::default().use_book(false).hash_size(1).seed(5).build();
+        let mv = e.legal_moves()[0];
+        assert!(e.make_move(mv));
+        let info = e.search(Limits::new().depth(4));
+        assert!(info.hashfull > 0);
+
+        e.set_hash_size(4);
+        assert_eq!(e.hash_size_bytes(), 4 * 1024 * 1024);
+        assert_eq!(e.history_len(), 1);
+        assert_eq!(e.tt.hashfull(), 0);
+
+        e.search(Limits::new().depth(4));
+        e.clear_hash();
+        assert!(e.tt.probe(e.position().zobrist_key()).is_none());
+    }
+
+    #[test]
+    fn analysis_cache_answers_repeat_searches() {
+        let path = std::env::temp_dir().join(format!("chessai-engine-cache-{}.bin", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cache = AnalysisCache::open(&path, 64).unwrap();
+        let mut e = EngineBuilder::default().use_book(false).analysis_cache(cache).build();
+        let first = e.search(Limits::new().depth(4));
+        assert_eq!(e.analysis_cache_mut().unwrap().len(), 1);
+
+        // Same depth or shallower: served from the cache, no new search.
+        let mut iterations = 0;
+        let again = e.search_with(Limits::new().depth(3), |_| iterations += 1);
+        assert_eq!(iterations, 1);
+        assert_eq!((again.depth, again.score, &again.pv), (first.depth, first.score, &first.pv));
+        assert_eq!(again.time, Duration::ZERO);
+
+        // Deeper: searched and written back.
+        assert_eq!(e.search(Limits::new().depth(5)).depth, 5);
+
+        // Searches without a depth limit: any entry for a time limit, enough nodes for a
+        // node limit.
+        let mut iterations = 0;
+        assert_eq!(e.search_with(Limits::new().time(Duration::from_secs(5)), |_| iterations += 1).depth, 5);
+        let nodes = e.search_with(Limits::new().nodes(1), |_| iterations += 1).nodes;
+        assert_eq!(iterations, 2);
+        e.search_with(Limits::new().nodes(nodes + 100_000), |_| iterations += 1);
+        assert!(iterations > 3);
+        drop(e);
+
+        // Another contempt, or a deterministic engine, does not see these entries.
+        let cache = AnalysisCache::open(&path, 64).unwrap();
+        let mut e = EngineBuilder::default().use_book(false).analysis_cache(cache).build();
+        let mut iterations = 0;
+        e.search_with(Limits::new().depth(3), |_| iterations += 1);
+        assert_eq!(iterations, 1, "reopened cache answers");
+        e.search_with(Limits::new().depth(3).contempt(50), |_| iterations += 1);
+        assert_eq!(iterations, 4, "other contempt searches");
+        let cache = e.analysis_cache.take().unwrap();
+        let mut e = EngineBuilder::default().use_book(false).deterministic(true).analysis_cache(cache).build();
+        let mut iterations = 0;
+        e.search_with(Limits::new().depth(3), |_| iterations += 1);
+        assert_eq!(iterations, 3, "deterministic searches");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn aspiration_failures_are_reported_as_bounds() {
+        // A middlegame position whose depth-8 iteration fails its aspiration window once.
+        let mut e = EngineBuilder::default().threads(1).use_book(false).build();
+        e.set_fen("3akab2/9/2n1b1nc1/p1N1p1p1p/2P2R3/9/4r1P1P/7C1/1C7/2BAKAB2 w").unwrap();
+        let mut bounds = Vec::new();
+        let info = e.search_with(Limits::new().depth(8), |info| bounds.push((info.depth, info.bound)));
+        assert_eq!(info.bound, ScoreBound::Exact);
+        assert!(bounds.iter().any(|&(depth, bound)| depth >= 5 && bound != ScoreBound::Exact));
+        assert_eq!(bounds.last(), Some(&(8, ScoreBound::Exact)));
+    }
+
+    #[test]
+    fn game_status_adjudicates_mate_repetition_and_move_limit() {
+        let mut e = EngineBuilder::default().use_book(false).build();
+        assert_eq!(e.game_status(), GameStatus::Ongoing);
+        for _ in 0..2 {
+            for iccs in ["h0g2", "h9g7", "g2h0", "g7h9"] {
+                assert!(e.make_move(Move::from_iccs(iccs).unwrap()));
+            }
+        }
+        assert_eq!(e.game_status(), GameStatus::Repetition);
+        assert_eq!(e.game_status().red_score(), Some(0.5));
+
+        // Rook to d0 mates.
+        e.set_fen("3k5/9/9/9/9/9/9/9/9/R3K4 w").unwrap();
+        assert!(e.make_move(Move::from_iccs("a0d0").unwrap()));
+        assert_eq!(e.game_status(), GameStatus::Mate { winner: Color::Red });
+
+        // Shuffle elephants without repeating a position until the move limit strikes.
+        e.set_fen("2bk5/9/4b4/9/9/9/9/4B4/9/2B1K4 w").unwrap();
+        let mut seen = vec![e.position().zobrist_key()];
+        while e.game_status() == GameStatus::Ongoing {
+            let fresh = e.legal_moves().into_iter().find(|&mv| {
+                e.make_move(mv);
+                let ok = !seen.contains(&e.position().zobrist_key()) && e.game_status() != GameStatus::Repetition;
+                e.undo_move();
+                ok && e.position().piece_at(mv.dst()).is_none()
+            });
+            e.make_move(fresh.expect("an unvisited position"));
+            seen.push(e.position().zobrist_key());
+        }
+        assert_eq!(e.game_status(), GameStatus::MoveLimit);
+        assert_eq!(e.history_len(), MOVE_LIMIT_PLIES as usize);
+    }
+
     #[test]
     fn multi_ply_unwind_to_startpos() {
         let mut e = Engine::default();
@@ -423,6 +1122,16 @@ mod tests {
         assert_eq!(e.fen(), fen0);
     }
 
+    #[test]
+    fn eval_params_survive_set_fen() {
+        let params = EvalParams { advanced_value: 42, ..EvalParams::default() };
+        let mut e = Engine::builder().eval_params(params.clone()).build();
+        assert_eq!(e.position().eval_params(), &params);
+        e.set_fen("3k5/9/9/9/9/9/9/9/9/5K3 w").unwrap();
+        assert_eq!(e.position().eval_params(), &params);
+        assert_eq!(crate::eval::evaluate(e.position()), 42);
+    }
+
     #[test]
     fn move_history_iter_returns_played_moves_in_order() {
         let mut e = Engine::default();
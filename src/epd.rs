@@ -0,0 +1,277 @@
+//! EPD-style test suites: a FEN followed by `;`-terminated opcodes, and a runner that
+//! searches every position and checks the engine's answer.
+//!
+//! ```text
+//! 3k5/9/9/9/9/9/9/9/9/R3K4 w - - bm a0d0; dm 1; id "rook mate";
+//! ```
+//!
+//! Understood opcodes are `bm` (best moves), `am` (moves to avoid), `dm` (mate in that
+//! many moves) and `id`; others are kept verbatim. Moves are ICCS, with or without the dash.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::engine::Engine;
+use crate::error::ChessAIError;
+use crate::limits::Limits;
+use crate::mv::Move;
+use crate::position::Position;
+use crate::search::ScoreBound;
+use crate::search::SearchInfo;
+
+/// One parsed EPD line.
+#[derive(Clone, Debug)]
+pub struct EpdRecord {
+    pub position: Position,
+    /// `id`, unquoted.
+    pub id: Option<String>,
+    /// `bm`: any of these solves the position.
+    pub best_moves: Vec<Move>,
+    /// `am`: none of these may be played.
+    pub avoid_moves: Vec<Move>,
+    /// `dm`: the side to move mates in at most this many moves.
+    pub mate_in: Option<u32>,
+    /// Other opcodes and their operands, in order of appearance.
+    pub other: Vec<(String, String)>,
+}
+
+impl Position {
+    /// Parse an EPD line: the FEN board and side to move, optional `-` / move-counter
+    /// fields, then the opcodes. `bm` and `am` moves must be legal in the position.
+    pub fn from_epd(line: &str) -> Result<EpdRecord, ChessAIError> { line.parse() }
+}
+
+impl FromStr for EpdRecord {
+    type Err = ChessAIError;
+
+    fn from_str(line: &str) -> Result<EpdRecord, ChessAIError> {
+        let bad = |reason: &str| ChessAIError::BadEpd(format!("{reason} in {:?}", line.trim()));
+        let mut fields = line.split_ascii_whitespace();
+        let board = fields.next().ok_or_else(|| bad("empty line"))?;
+        let side = fields.next().ok_or_else(|| bad("missing side to move"))?;
+        let position = Position::from_fen(&format!("{board} {side}"))?;
+
+        // Skip the placeholder and counter fields; the first other word starts the opcodes.
+        let mut rest = line.trim_start();
+        for _ in 0..2 {
+            rest = rest.trim_start().split_once(char::is_whitespace).map_or("", |(_, tail)| tail);
+        }
+        loop {
+            let trimmed = rest.trim_start();
+            let (word, tail) = trimmed.split_once(char::is_whitespace).unwrap_or((trimmed, ""));
+            if word.is_empty() || (word != "-" && word.parse::<u32>().is_err()) {
+                rest = trimmed;
+                break;
+            }
+            rest = tail;
+        }
+
+        let mut record = EpdRecord {
+            position,
+            id: None,
+            best_moves: Vec::new(),
+            avoid_moves: Vec::new(),
+            mate_in: None,
+            other: Vec::new(),
+        };
+        for op in split_operations(rest) {
+            let (opcode, operand) = op.split_once(char::is_whitespace).unwrap_or((op, ""));
+            let operand = operand.trim();
+            let moves = || -> Result<Vec<Move>, ChessAIError> {
+                let moves = operand.split_ascii_whitespace().map(Move::from_iccs).collect::<Result<Vec<_>, _>>()?;
+                match moves.iter().find(|&&mv| !record.position.is_legal(mv)) {
+                    Some(mv) => Err(bad(&format!("illegal move {mv}"))),
+                    None if moves.is_empty() => Err(bad(&format!("{opcode} without moves"))),
+                    None => Ok(moves),
+                }
+            };
+            match opcode {
+                "bm" => record.best_moves = moves()?,
+                "am" => record.avoid_moves = moves()?,
+                "dm" => record.mate_in = Some(operand.parse().map_err(|_| bad("dm needs a move count"))?),
+                "id" => record.id = Some(operand.trim_matches('"').to_string()),
+                _ => record.other.push((opcode.to_string(), operand.to_string())),
+            }
+        }
+        Ok(record)
+    }
+}
+
+/// The `;`-terminated operations of `ops`, trimmed, ignoring semicolons inside quotes.
+fn split_operations(ops: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let (mut start, mut quoted) = (0, false);
+    for (i, c) in ops.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                out.push(ops[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    out.push(ops[start..].trim());
+    out.retain(|op| !op.is_empty());
+    out
+}
+
+impl EpdRecord {
+    /// Whether a search result meets every `bm` / `am` / `dm` condition.
+    pub fn is_solved_by(&self, info: &SearchInfo) -> bool {
+        let Some(mv) = info.best_move else { return false };
+        (self.best_moves.is_empty() || self.best_moves.contains(&mv))
+            && !self.avoid_moves.contains(&mv)
+            && self.mate_in.is_none_or(|n| info.mate_in.is_some_and(|m| m > 0 && m as u32 <= n))
+    }
+}
+
+/// Outcome of one suite position.
+#[derive(Clone, Debug)]
+pub struct EpdResult {
+    /// The record's `id`, or its 1-based line number in the suite.
+    pub id: String,
+    pub solved: bool,
+    /// When the final answer was first reached (and kept until the end), if solved.
+    pub time_to_solve: Option<Duration>,
+    pub best_move: Option<Move>,
+    pub depth: u8,
+    pub nodes: u64,
+    pub time: Duration,
+}
+
+/// Outcome of a whole suite. `Display` prints one `key value` line per position and a
+/// final `total` line, stable enough to diff between builds.
+#[derive(Clone, Debug, Default)]
+pub struct EpdReport {
+    pub results: Vec<EpdResult>,
+}
+
+impl EpdReport {
+    pub fn solved(&self) -> usize { self.results.iter().filter(|r| r.solved).count() }
+
+    pub fn nodes(&self) -> u64 { self.results.iter().map(|r| r.nodes).sum() }
+
+    pub fn time(&self) -> Duration { self.results.iter().map(|r| r.time).sum() }
+}
+
+impl fmt::Display for EpdResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let best = self.best_move.map_or_else(|| "none".to_string(), |mv| mv.to_iccs());
+        write!(f, "id {:?} result {} ", self.id, if self.solved { "solved" } else { "failed" })?;
+        if let Some(t) = self.time_to_solve {
+            write!(f, "solve_ms {} ", t.as_millis())?;
+        }
+        write!(f, "move {best} depth {} nodes {} time_ms {}", self.depth, self.nodes, self.time.as_millis())
+    }
+}
+
+impl fmt::Display for EpdReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            writeln!(f, "{result}")?;
+        }
+        write!(
+            f,
+            "total {} solved {} failed {} nodes {} time_ms {}",
+            self.results.len(),
+            self.solved(),
+            self.results.len() - self.solved(),
+            self.nodes(),
+            self.time().as_millis()
+        )
+    }
+}
+
+/// Search every record with `limits` on `engine` and check the answers. `progress` sees
+/// each result as it is decided. The engine's position is replaced by each record's in
+/// turn, which also clears its hash.
+pub fn run_epd(
+    engine: &mut Engine,
+    records: &[EpdRecord],
+    limits: Limits,
+    mut progress: impl FnMut(&EpdResult),
+) -> EpdReport {
+    let mut report = EpdReport::default();
+    for (idx, record) in records.iter().enumerate() {
+        engine.set_fen(&record.position.to_fen()).expect("EPD positions are valid");
+        let mut solved_at = None;
+        let info = engine.search_with(limits, |info| {
+            if info.bound != ScoreBound::Exact {
+                return;
+            }
+            match record.is_solved_by(info) {
+                true => solved_at = solved_at.or(Some(info.time)),
+                false => solved_at = None,
+            }
+        });
+        let solved = record.is_solved_by(&info);
+        let result = EpdResult {
+            id: record.id.clone().unwrap_or_else(|| (idx + 1).to_string()),
+            solved,
+            time_to_solve: if solved { solved_at.or(Some(info.time)) } else { None },
+            best_move: info.best_move,
+            depth: info.depth,
+            nodes: info.nodes,
+            time: info.time,
+        };
+        progress(&result);
+        report.results.push(result);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUITE: &str = r#"
+3k5/9/9/9/9/9/9/9/9/R3K4 w - - 0 1 bm a0d0; dm 1; id "rook mate";
+3k5/9/9/9/9/9/9/9/4C4/R3K4 w dm 2; id "cannon; rook";
+3k5/9/9/9/9/9/9/9/9/R3K4 w am a0d0; c0 "comment";
+"#;
+
+    fn suite() -> Vec<EpdRecord> {
+        SUITE.lines().filter(|l| !l.trim().is_empty()).map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parses_opcodes() {
+        let records = suite();
+        assert_eq!(records[0].id.as_deref(), Some("rook mate"));
+        assert_eq!(records[0].best_moves, [Move::from_iccs("a0d0").unwrap()]);
+        assert_eq!(records[0].mate_in, Some(1));
+        assert_eq!(records[1].id.as_deref(), Some("cannon; rook"));
+        assert_eq!(records[1].mate_in, Some(2));
+        assert_eq!(records[2].avoid_moves, [Move::from_iccs("a0-d0").unwrap()]);
+        assert_eq!(records[2].other, [("c0".to_string(), "\"comment\"".to_string())]);
+
+        // Trailing placeholder and counter fields are not opcodes.
+        for line in ["3k5/9/9/9/9/9/9/9/9/R3K4 w - - 0 1", "3k5/9/9/9/9/9/9/9/9/R3K4 w -", "3k5/9/9/9/9/9/9/9/9/R3K4 w"]
+        {
+            let record = Position::from_epd(line).unwrap();
+            assert!(record.other.is_empty() && record.best_moves.is_empty(), "{line}: {record:?}");
+        }
+
+        assert!(Position::from_epd("3k5/9/9/9/9/9/9/9/9/R3K4 w bm a0b1;").is_err());
+        assert!(Position::from_epd("3k5/9/9/9/9/9/9/9/9/R3K4 w dm x;").is_err());
+        assert!(Position::from_epd("").is_err());
+    }
+
+    #[test]
+    fn runner_reports_solved_and_failed() {
+        let mut engine = Engine::builder().use_book(false).hash_size(1).build();
+        let mut seen = Vec::new();
+        let report = run_epd(&mut engine, &suite(), Limits::new().depth(5), |r| seen.push(r.id.clone()));
+        assert_eq!(seen, ["rook mate", "cannon; rook", "3"]);
+        assert_eq!(report.results.iter().map(|r| r.solved).collect::<Vec<_>>(), [true, true, false]);
+        assert!(report.results[0].time_to_solve.is_some());
+        assert!(report.results[2].time_to_solve.is_none());
+        assert!(report.to_string().ends_with(&format!(
+            "total 3 solved 2 failed 1 nodes {} time_ms {}",
+            report.nodes(),
+            report.time().as_millis()
+        )));
+    }
+}
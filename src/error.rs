@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::mv::Move;
+
 /// All errors surfaced by the public API.
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
 pub enum ChessAIError {
@@ -17,4 +19,32 @@ pub enum ChessAIError {
     BadIccsSquare(String),
     #[error("cannot parse move from {0:?}; expected e.g. b2-e2 or h2e2")]
     BadIccsMove(String),
+    #[error("cannot parse WXF move {0:?} in this position; expected e.g. C2.5 or H8+7")]
+    BadWxfMove(String),
+    #[error("illegal move {mv} at ply {ply} of the game")]
+    IllegalGameMove { ply: usize, mv: Move },
+    #[error("cannot read move {text:?} at ply {ply} of the game; expected a legal ICCS or WXF move")]
+    BadGameMove { ply: usize, text: String },
+    #[error("cannot parse tuning record from {0:?}; expected `<fen> | <result>`")]
+    BadTuningRecord(String),
+    #[error("cannot parse self-play record from {0:?}; expected `<fen> | <score> | <move> | <result>`")]
+    BadDataRecord(String),
+    #[error("cannot parse EPD record: {0}")]
+    BadEpd(String),
+    #[error("invalid evaluation parameters at line {line}: {reason}")]
+    BadEvalParams { line: usize, reason: String },
+    #[error("invalid search parameters at line {line}: {reason}")]
+    BadSearchParams { line: usize, reason: String },
+    #[error("I/O error: {0}")]
+    Io(String),
+    #[error("invalid hash file: {0}")]
+    BadHashFile(String),
+    #[error("invalid analysis cache file: {0}")]
+    BadCacheFile(String),
+    #[error("tablebase error: {0}")]
+    BadTablebase(String),
+}
+
+impl From<std::io::Error> for ChessAIError {
+    fn from(err: std::io::Error) -> Self { ChessAIError::Io(err.to_string()) }
 }
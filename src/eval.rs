@@ -1,5 +1,10 @@
+use std::fmt;
+use std::str::FromStr;
+
 use crate::color::Color;
+use crate::error::ChessAIError;
 use crate::piece::Piece;
+use crate::piece::PieceType;
 use crate::position::Position;
 use crate::square::Square;
 
@@ -10,8 +15,15 @@ pub const WIN_VALUE: i32 = MATE_VALUE - 200;
 pub const NULL_OKAY_MARGIN: i32 = 200;
 pub const DRAW_VALUE: i32 = 20;
 
+/// Material count per piece type (traditional Chinese-chess point count, indexed by
+/// `PieceType::index`). Only feeds the null-move material check; the PST already carries
+/// each piece's worth for evaluation purposes. [`TexelTuner`](crate::TexelTuner) tunes
+/// them as the level of each table.
+pub const PIECE_VALUES: [i32; PieceType::COUNT] = [10_000, 20, 20, 40, 90, 45, 10];
+
 /// Piece-square tables, one per piece type, indexed by **red-perspective** square
-/// (`0..=89`, rank 0 = red back). For black evaluation, pass `sq.flip_rank()`.
+/// (`0..=89`, rank 0 = red back). For black evaluation, pass `sq.flip_rank()`. These are
+/// the defaults behind [`EvalParams`].
 pub const PST: [[i16; 90]; 7] = {
     const KING_OR_PAWN: [i16; 90] = [
         // rank 0 (red back)
@@ -80,21 +92,10 @@ pub const PST: [[i16; 90]; 7] = {
     [KING_OR_PAWN, ADVISOR_OR_BISHOP, ADVISOR_OR_BISHOP, KNIGHT, ROOK, CANNON, KING_OR_PAWN]
 };
 
-/// Look up the PST contribution of a single piece on a single square. Black uses the
-/// rank-flipped index, giving a natural symmetry.
-#[inline]
-pub const fn psq_value(piece: Piece, sq: Square) -> i16 {
-    let idx = match piece.color() {
-        Color::Red => sq.raw() as usize,
-        Color::Black => sq.flip_rank().raw() as usize,
-    };
-    PST[piece.kind().index()][idx]
-}
-
 /// Material/positional score from the side-to-move's perspective, in centipawn units.
 #[inline]
 pub fn evaluate(pos: &Position) -> i32 {
-    let red_score = pos.psq_score() + ADVANCED_VALUE;
+    let red_score = pos.psq_score() + pos.eval_params().advanced_value;
     match pos.side_to_move() {
         Color::Red => red_score,
         Color::Black => -red_score,
@@ -102,15 +103,157 @@ pub fn evaluate(pos: &Position) -> i32 {
 }
 
 /// Distance-to-mate-aware draw sentinel, switched by ply parity so repetition favors the
-/// side with the edge instead of letting one side force a cycle arbitrarily.
+/// side with the edge instead of letting one side force a cycle arbitrarily. `contempt` is
+/// taken from the root side's perspective: a draw is worth `-contempt` to it.
 #[inline]
-pub fn draw_value(ply_from_root: u32) -> i32 { if ply_from_root & 1 == 0 { -DRAW_VALUE } else { DRAW_VALUE } }
+pub fn draw_value(contempt: i32, ply_from_root: u32) -> i32 {
+    if ply_from_root & 1 == 0 { -contempt } else { contempt }
+}
+
+// --------------------------------------------------------------------
+// Runtime-loadable parameters
+// --------------------------------------------------------------------
+
+/// Names of the piece types in parameter files, indexed by `PieceType::index`.
+pub(crate) const PIECE_NAMES: [&str; PieceType::COUNT] =
+    ["king", "advisor", "bishop", "knight", "rook", "cannon", "pawn"];
+
+/// Tokens of a line-oriented parameter file with their 1-based line numbers, `#` comments
+/// stripped, so multi-line blocks read naturally. Shared by the `EvalParams` and
+/// `SearchParams` text formats.
+pub(crate) fn param_tokens(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .flat_map(|(i, l)| l.split('#').next().unwrap_or("").split_ascii_whitespace().map(move |t| (i + 1, t)))
+}
+
+/// Evaluation parameters an `Engine` is built with. `Default` reproduces the compile-time
+/// tables above, so experiments only need to override what they change.
+///
+/// The text form (see `Display` / `FromStr`) is line-oriented; `#` starts a comment:
+///
+/// ```text
+/// advanced_value 3
+/// draw_value 20
+/// null_okay_margin 200
+/// piece_values 10000 20 20 40 90 45 10
+/// pst rook
+/// 194 206 204 212 200 212 204 206 194
+/// ...                                  # 10 rows of 9, rank 0 (red back rank) first
+/// ```
+///
+/// Keys may appear in any order and any subset; omitted keys keep their default.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvalParams {
+    /// Piece-square tables by `PieceType`, indexed by red-perspective square.
+    pub pst: [[i16; 90]; PieceType::COUNT],
+    /// Material count by `PieceType` (see [`PIECE_VALUES`]).
+    pub piece_values: [i32; PieceType::COUNT],
+    /// Bonus added to red's score.
+    pub advanced_value: i32,
+    /// Default contempt: magnitude of the ply-parity draw score (see [`draw_value`]).
+    pub draw_value: i32,
+    /// Minimum own material for null-move pruning.
+    pub null_okay_margin: i32,
+}
+
+impl Default for EvalParams {
+    fn default() -> Self { DEFAULT_EVAL_PARAMS.clone() }
+}
+
+/// Shared default instance, so positions created without an engine don't each allocate.
+pub(crate) static DEFAULT_EVAL_PARAMS: EvalParams = EvalParams {
+    pst: PST,
+    piece_values: PIECE_VALUES,
+    advanced_value: ADVANCED_VALUE,
+    draw_value: DRAW_VALUE,
+    null_okay_margin: NULL_OKAY_MARGIN,
+};
+
+impl EvalParams {
+    /// Look up the PST contribution of a single piece on a single square. Black uses the
+    /// rank-flipped index, giving a natural symmetry.
+    #[inline]
+    pub fn psq(&self, piece: Piece, sq: Square) -> i16 {
+        let idx = match piece.color() {
+            Color::Red => sq.raw() as usize,
+            Color::Black => sq.flip_rank().raw() as usize,
+        };
+        self.pst[piece.kind().index()][idx]
+    }
+
+    #[inline]
+    pub fn piece_value(&self, kind: PieceType) -> i32 { self.piece_values[kind.index()] }
+}
+
+impl fmt::Display for EvalParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# chessai evaluation parameters")?;
+        writeln!(f, "advanced_value {}", self.advanced_value)?;
+        writeln!(f, "draw_value {}", self.draw_value)?;
+        writeln!(f, "null_okay_margin {}", self.null_okay_margin)?;
+        write!(f, "piece_values")?;
+        for v in self.piece_values {
+            write!(f, " {v}")?;
+        }
+        writeln!(f)?;
+        for (kind, table) in self.pst.iter().enumerate() {
+            writeln!(f, "pst {}", PIECE_NAMES[kind])?;
+            for row in table.chunks(9) {
+                let cells: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+                writeln!(f, "{}", cells.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for EvalParams {
+    type Err = ChessAIError;
+
+    fn from_str(text: &str) -> Result<EvalParams, ChessAIError> {
+        let mut tokens = param_tokens(text);
+        let err = |line: usize, reason: String| ChessAIError::BadEvalParams { line, reason };
+
+        let mut params = EvalParams::default();
+        while let Some((line, key)) = tokens.next() {
+            let mut number = |what: &str| -> Result<i32, ChessAIError> {
+                let (l, tok) = tokens.next().ok_or_else(|| err(line, format!("missing value for {what}")))?;
+                tok.parse::<i32>().map_err(|_| err(l, format!("{tok:?} is not an integer")))
+            };
+            match key {
+                "advanced_value" => params.advanced_value = number(key)?,
+                "draw_value" => params.draw_value = number(key)?,
+                "null_okay_margin" => params.null_okay_margin = number(key)?,
+                "piece_values" => {
+                    for slot in params.piece_values.iter_mut() {
+                        *slot = number(key)?;
+                    }
+                }
+                "pst" => {
+                    let (_, name) = tokens.next().ok_or_else(|| err(line, "missing piece name after pst".into()))?;
+                    let kind = PIECE_NAMES
+                        .iter()
+                        .position(|n| *n == name)
+                        .ok_or_else(|| err(line, format!("unknown piece {name:?}")))?;
+                    for slot in params.pst[kind].iter_mut() {
+                        let (l, tok) = tokens.next().ok_or_else(|| err(line, format!("pst {name} needs 90 values")))?;
+                        *slot = tok.parse::<i16>().map_err(|_| err(l, format!("{tok:?} is not a PST value")))?;
+                    }
+                }
+                other => return Err(err(line, format!("unknown key {other:?}"))),
+            }
+        }
+        Ok(params)
+    }
+}
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
     use crate::fen::STARTING_FEN;
-    use crate::piece::PieceType;
 
     #[test]
     fn startpos_is_balanced() {
@@ -124,6 +267,39 @@ mod tests {
     fn rook_initial_psq_matches() {
         let sq = Square::from_iccs("a0").unwrap();
         let piece = Piece::new(Color::Red, PieceType::Rook);
-        assert_eq!(psq_value(piece, sq), 194);
+        assert_eq!(EvalParams::default().psq(piece, sq), 194);
+    }
+
+    #[test]
+    fn params_text_roundtrip() {
+        let mut params = EvalParams::default();
+        params.pst[PieceType::Cannon.index()][40] = -7;
+        params.draw_value = 35;
+        let parsed: EvalParams = params.to_string().parse().unwrap();
+        assert_eq!(parsed, params);
+    }
+
+    #[test]
+    fn partial_params_keep_defaults() {
+        let parsed: EvalParams = "# just one tweak\nadvanced_value 9\n".parse().unwrap();
+        assert_eq!(parsed, EvalParams { advanced_value: 9, ..EvalParams::default() });
+    }
+
+    #[test]
+    fn bad_params_report_line() {
+        let err = "draw_value 20\npst rook 1 2 x".parse::<EvalParams>().unwrap_err();
+        assert!(matches!(err, ChessAIError::BadEvalParams { line: 2, .. }), "{err:?}");
+        assert!("bogus 1".parse::<EvalParams>().is_err());
+        assert!("pst rook 1 2 3".parse::<EvalParams>().is_err());
+    }
+
+    #[test]
+    fn custom_params_change_evaluation() {
+        let mut pos = Position::from_fen("3k5/9/9/9/9/9/9/9/9/R4K3 w").unwrap();
+        let base = evaluate(&pos);
+        let mut params = EvalParams::default();
+        params.pst[PieceType::Rook.index()][0] += 50;
+        pos.set_eval_params(Arc::new(params));
+        assert_eq!(evaluate(&pos), base + 50);
     }
 }
@@ -0,0 +1,181 @@
+//! Game records: PGN-style tag pairs, a start position and the moves played.
+//!
+//! Reading accepts PGN (`[Tag "value"]` lines, `{comments}`, move numbers and a result
+//! marker) as well as bare move lists, with moves in ICCS (`h2e2`, `h2-e2`) or WXF
+//! (`C2.5`). Writing always produces PGN with ICCS moves and a `[Format "ICCS"]` tag.
+//!
+//! ```
+//! use chessai::Game;
+//!
+//! let game: Game = "[Result \"1-0\"]\n1. h2e2 h9g7 2. H2+3 {book}".parse().unwrap();
+//! assert_eq!(game.moves.len(), 3);
+//! assert_eq!(game.result(), "1-0");
+//! assert!(game.to_string().ends_with("1. h2-e2 h9-g7 2. h0-g2 1-0"));
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::color::Color;
+use crate::error::ChessAIError;
+use crate::fen::STARTING_FEN;
+use crate::mv::Move;
+use crate::position::Position;
+
+/// A parsed game. Displays as PGN: the tags, `FEN` unless the game starts from the start
+/// position, then the numbered ICCS moves and the result.
+#[derive(Clone, Debug)]
+pub struct Game {
+    /// Tag pairs in input order, without `FEN` and `Format`, which the writer derives.
+    pub tags: Vec<(String, String)>,
+    /// Position before the first move: the `FEN` tag, else the start position.
+    pub start: Position,
+    pub moves: Vec<Move>,
+}
+
+impl Game {
+    /// Parse `text` as [`FromStr`] does, starting from `fen` when the game has no `FEN`
+    /// tag (a bare move list from a position).
+    pub fn parse(text: &str, fen: Option<&str>) -> Result<Game, ChessAIError> {
+        let mut tags = Vec::new();
+        let mut body = String::new();
+        for line in text.lines() {
+            let line = line.trim();
+            match line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                Some(tag) => {
+                    let (key, value) = tag.split_once(' ').unwrap_or((tag, ""));
+                    tags.push((key.to_string(), value.trim().trim_matches('"').to_string()));
+                }
+                None => {
+                    body.push_str(line);
+                    body.push(' ');
+                }
+            }
+        }
+        let fen = tags.iter().find(|(key, _)| key == "FEN").map(|(_, value)| value.as_str()).or(fen);
+        let start = Position::from_fen(fen.unwrap_or(STARTING_FEN))?;
+
+        let mut pos = start.clone();
+        let mut moves = Vec::new();
+        let mut comment = false;
+        for token in body.split_ascii_whitespace() {
+            if comment || token.starts_with('{') {
+                comment = !token.ends_with('}');
+                continue;
+            }
+            if matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*") {
+                continue;
+            }
+            // Move numbers: `12.`, `12...`, a bare `...`, or glued to the move as `12.h2e2`.
+            // A crowded-pawn WXF move such as `27.8` is not one.
+            let number = token.trim_start_matches(|c: char| c.is_ascii_digit());
+            let rest = number.trim_start_matches('.');
+            let token = match rest.len() < number.len() && (rest.is_empty() || rest.len() >= 4) {
+                true => rest,
+                false => token,
+            };
+            if token.is_empty() {
+                continue;
+            }
+            let mv = parse_move(&pos, token)
+                .ok_or_else(|| ChessAIError::BadGameMove { ply: moves.len() + 1, text: token.to_string() })?;
+            pos.try_make_move(mv);
+            moves.push(mv);
+        }
+        tags.retain(|(key, _)| key != "FEN" && key != "Format");
+        Ok(Game { tags, start, moves })
+    }
+
+    /// The `Result` tag, or `*` when there is none.
+    pub fn result(&self) -> &str {
+        self.tags.iter().find(|(key, _)| key == "Result").map_or("*", |(_, value)| value.as_str())
+    }
+}
+
+/// `text` as a legal move in `pos`, in ICCS or WXF notation.
+fn parse_move(pos: &Position, text: &str) -> Option<Move> {
+    Move::from_iccs(text).ok().filter(|&mv| pos.is_legal(mv)).or_else(|| pos.parse_wxf(text).ok())
+}
+
+impl FromStr for Game {
+    type Err = ChessAIError;
+
+    fn from_str(text: &str) -> Result<Game, ChessAIError> { Game::parse(text, None) }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.tags {
+            writeln!(f, "[{key} \"{value}\"]")?;
+        }
+        let start_fen = self.start.to_fen();
+        if Position::from_fen(STARTING_FEN).is_ok_and(|p| p.to_fen() != start_fen) {
+            writeln!(f, "[FEN \"{start_fen}\"]")?;
+        }
+        writeln!(f, "[Format \"ICCS\"]\n")?;
+        // Number full moves, starting from the side to move in the start position.
+        let offset = usize::from(self.start.side_to_move() == Color::Black);
+        if offset == 1 && !self.moves.is_empty() {
+            write!(f, "1. ... ")?;
+        }
+        for (ply, mv) in self.moves.iter().enumerate() {
+            let ply = ply + offset;
+            if ply % 2 == 0 {
+                write!(f, "{}. ", ply / 2 + 1)?;
+            }
+            write!(f, "{} ", mv.to_iccs())?;
+        }
+        write!(f, "{}", self.result())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_pgn_and_bare_move_lists() {
+        let pgn = r#"
+[Event "club"]
+[FEN "3k5/9/9/9/9/9/9/9/9/R3K4 w - - 0 1"]
+[Format "WXF"]
+[Result "1-0"]
+
+1. R9.6 {mate} 1-0
+"#;
+        let game: Game = pgn.parse().unwrap();
+        assert_eq!(game.tags, [("Event".to_string(), "club".to_string()), ("Result".to_string(), "1-0".to_string())]);
+        assert_eq!(game.start.to_fen(), Position::from_fen("3k5/9/9/9/9/9/9/9/9/R3K4 w").unwrap().to_fen());
+        assert_eq!(game.moves, [Move::from_iccs("a0d0").unwrap()]);
+
+        // Bare lists: glued move numbers, dashes, WXF, and a start position from outside.
+        let game: Game = "1.h2e2 h9-g7 2...H2+3".parse().unwrap();
+        assert_eq!(game.moves.iter().map(|mv| mv.to_iccs()).collect::<Vec<_>>(), ["h2-e2", "h9-g7", "h0-g2"]);
+        assert_eq!(game.result(), "*");
+        let game = Game::parse("d9e9", Some("3k5/9/9/9/9/9/9/9/9/R4K3 b")).unwrap();
+        assert_eq!(game.start.side_to_move(), Color::Black);
+
+        let game = Game::parse("1. 17+1 1... d9e9 2.27.8", Some("3k5/9/2P6/2P6/2P6/9/9/9/9/5K3 w")).unwrap();
+        assert_eq!(game.moves.iter().map(|mv| mv.to_iccs()).collect::<Vec<_>>(), ["c7-c8", "d9-e9", "c6-b6"]);
+
+        assert_eq!(
+            "1. h2e2 h2e2".parse::<Game>().unwrap_err(),
+            ChessAIError::BadGameMove { ply: 2, text: "h2e2".to_string() }
+        );
+        assert!(Game::parse("", Some("not a fen")).is_err());
+    }
+
+    #[test]
+    fn writes_pgn_that_reads_back() {
+        let game: Game = "[Result \"0-1\"]\nh2e2 h7e7".parse().unwrap();
+        assert_eq!(game.to_string(), "[Result \"0-1\"]\n[Format \"ICCS\"]\n\n1. h2-e2 h7-e7 0-1");
+
+        // From a black-to-move position: the FEN is kept and numbering starts with `1. ...`.
+        let game = Game::parse("d9e9 a0a9", Some("3k5/9/9/9/9/9/9/9/9/R4K3 b")).unwrap();
+        let pgn = game.to_string();
+        assert!(pgn.starts_with("[FEN \"3k5/9/9/9/9/9/9/9/9/R4K3 b"), "{pgn}");
+        assert!(pgn.ends_with("1. ... d9-e9 2. a0-a9 *"), "{pgn}");
+        let again: Game = pgn.parse().unwrap();
+        assert_eq!((again.start.to_fen(), again.moves), (game.start.to_fen(), game.moves));
+    }
+}
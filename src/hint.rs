@@ -0,0 +1,217 @@
+//! Teaching hints: the best few moves with what each of them does, and what the opponent
+//! would do if we passed.
+
+use std::time::Instant;
+
+use crate::engine::Engine;
+use crate::limits::Limits;
+use crate::mate::MateOutcome;
+use crate::mv::Move;
+use crate::piece::PieceType;
+use crate::search::mate_in;
+
+/// Candidate moves reported by [`Engine::hint`] unless `Limits::multipv` asks for more.
+pub const HINT_CANDIDATES: u8 = 3;
+
+/// Nodes the mate-threat probes of one [`Engine::hint`] call share when the limits set no
+/// node budget.
+const HINT_MATE_NODES: u64 = 200_000;
+
+/// One suggested move. Scores are from the side to move's point of view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub mv: Move,
+    pub score: i32,
+    pub pv: Vec<Move>,
+    /// Kind of the piece this move takes, if any.
+    pub captures: Option<PieceType>,
+    pub gives_check: bool,
+    /// The move parries the [threat](Hint::threat): afterwards the threatening move is
+    /// illegal, no longer wins material, or no longer checks. A mate threat is parried
+    /// only when the mate solver proves there is no longer a mate in as many moves.
+    pub defends: bool,
+}
+
+/// What the opponent would play after a pass.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Threat {
+    pub mv: Move,
+    /// Score of the opponent's search after the pass, from the opponent's point of view.
+    pub score: i32,
+    pub pv: Vec<Move>,
+    /// Kind of the piece the threat takes, if any.
+    pub captures: Option<PieceType>,
+    pub gives_check: bool,
+    /// Set when the opponent would mate in this many moves.
+    pub mate_in: Option<i32>,
+}
+
+/// Result of [`Engine::hint`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hint {
+    /// Best first.
+    pub candidates: Vec<Candidate>,
+    /// `None` when we are in check (there is no pass to make) or the opponent's best reply
+    /// to a pass neither mates, checks, nor wins material by exchange.
+    pub threat: Option<Threat>,
+}
+
+impl Hint {
+    pub fn best(&self) -> Option<&Candidate> { self.candidates.first() }
+}
+
+impl Engine {
+    /// Suggest moves for the current position: the top `max(HINT_CANDIDATES,
+    /// limits.multipv)` lines of a single-threaded search under `limits`, and the threat
+    /// found by a second search with the turn passed to the opponent. Each candidate is
+    /// tagged as a capture, check and/or defence against that threat, so a UI can put
+    /// the advice into words.
+    ///
+    /// The time and node limits cover the whole call: half goes to the candidate search,
+    /// half of the rest to the threat search, and what remains to checking each candidate
+    /// against a mate threat. A depth limit applies to both searches.
+    pub fn hint(&mut self, limits: Limits) -> Hint {
+        let mut budget = Budget::new(limits);
+        let lines = limits.multipv.max(HINT_CANDIDATES);
+        let info = self.search_root_moves(budget.share(2).multipv(lines), &[]);
+        budget.spend(info.nodes);
+
+        let threat = self.search_after_pass(budget.share(2).multipv(1)).and_then(|(reply, passed)| {
+            budget.spend(reply.nodes);
+            let mv = reply.best_move?;
+            let threat = Threat {
+                mv,
+                score: reply.score,
+                pv: reply.pv,
+                captures: passed.piece_at(mv.dst()).map(|p| p.kind()),
+                gives_check: passed.gives_check(mv),
+                mate_in: mate_in(reply.score).filter(|&n| n > 0),
+            };
+            let real =
+                threat.mate_in.is_some() || threat.gives_check || (threat.captures.is_some() && passed.see(mv) > 0);
+            real.then_some(threat)
+        });
+
+        // Without a node limit, the mate probes still share a fixed budget.
+        if budget.limits.max_nodes.is_none() {
+            budget.limits.max_nodes = Some(budget.used + HINT_MATE_NODES);
+        }
+        let pos = self.position().clone();
+        let lines: Vec<_> = info.lines.iter().filter(|line| !line.pv.is_empty()).collect();
+        let mut candidates = Vec::with_capacity(lines.len());
+        for (k, line) in lines.iter().enumerate() {
+            let mv = line.pv[0];
+            let parts = (lines.len() - k) as u32;
+            candidates.push(Candidate {
+                mv,
+                score: line.score,
+                pv: line.pv.clone(),
+                captures: pos.piece_at(mv.dst()).map(|p| p.kind()),
+                gives_check: pos.gives_check(mv),
+                defends: threat.as_ref().is_some_and(|t| self.parries(mv, t, &mut budget, parts)),
+            });
+        }
+        Hint { candidates, threat }
+    }
+
+    /// Whether `mv` takes the sting out of `threat`. A mate threat is re-checked with the
+    /// mate solver on `1/parts` of what is left of `budget`.
+    fn parries(&mut self, mv: Move, threat: &Threat, budget: &mut Budget, parts: u32) -> bool {
+        if let Some(moves) = threat.mate_in {
+            // The mate may start with another move once `mv` is on the board, so ask the
+            // solver rather than looking at `threat.mv`.
+            let moves = moves.clamp(1, u8::MAX as i32) as u8;
+            let (outcome, nodes) = self.mate_after(mv, moves, budget.share(parts));
+            budget.spend(nodes);
+            return outcome == MateOutcome::NoMate;
+        }
+        let mut after = self.position().clone();
+        after.make_move(mv);
+        if !after.is_legal(threat.mv) {
+            return true;
+        }
+        (threat.captures.is_some() && (after.piece_at(threat.mv.dst()).is_none() || after.see(threat.mv) <= 0))
+            || (threat.gives_check && !after.gives_check(threat.mv))
+    }
+}
+
+/// One time and node budget for a whole [`Engine::hint`] call, handed out phase by phase.
+struct Budget {
+    limits: Limits,
+    start: Instant,
+    used: u64,
+}
+
+impl Budget {
+    fn new(limits: Limits) -> Self { Budget { limits, start: Instant::now(), used: 0 } }
+
+    /// The caller's limits with `1/parts` of the time and nodes still left.
+    fn share(&self, parts: u32) -> Limits {
+        let mut limits = self.limits;
+        if let Some(time) = self.limits.max_time {
+            limits.max_time = Some(time.saturating_sub(self.start.elapsed()) / parts);
+        }
+        if let Some(nodes) = self.limits.max_nodes {
+            limits.max_nodes = Some(nodes.saturating_sub(self.used) / parts as u64);
+        }
+        limits
+    }
+
+    fn spend(&mut self, nodes: u64) { self.used += nodes; }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn explains_captures_checks_and_defences() {
+        let mut engine = Engine::builder().use_book(false).hash_size(1).build();
+
+        // Black's rook on e2 attacks the undefended red knight on c2; red can save it.
+        engine.set_fen("3k5/9/9/9/9/9/9/2N1r4/9/5K3 w").unwrap();
+        let fen = engine.fen();
+        let hint = engine.hint(Limits::new().depth(4));
+        assert_eq!(engine.fen(), fen);
+        assert_eq!(hint.candidates.len(), HINT_CANDIDATES as usize);
+        let threat = hint.threat.as_ref().expect("the knight hangs");
+        assert_eq!(threat.mv, Move::from_iccs("e2c2").unwrap());
+        assert_eq!(threat.captures, Some(PieceType::Knight));
+        let best = hint.best().unwrap();
+        assert!(best.defends, "{best:?}");
+        assert!(hint.candidates.windows(2).all(|w| w[0].score >= w[1].score));
+
+        // A free rook: the best move captures and nothing is threatened.
+        engine.set_fen("3k5/9/9/9/9/9/9/9/4r4/R3K4 w").unwrap();
+        let hint = engine.hint(Limits::new().depth(3));
+        let best = hint.best().unwrap();
+        assert_eq!(best.mv, Move::from_iccs("e0e1").unwrap());
+        assert_eq!(best.captures, Some(PieceType::Rook));
+        assert!(hint.threat.is_none(), "in check, there is no pass");
+
+        // Black threatens d5d0 mate; a rook on the back rank parries it, though the mating
+        // move stays legal and still checks.
+        engine.set_fen("3k5/9/9/9/3r5/R8/9/4p4/9/4K4 w").unwrap();
+        let hint = engine.hint(Limits::new().depth(4));
+        let threat = hint.threat.expect("a back-rank mate");
+        assert_eq!((threat.mv, threat.mate_in), (Move::from_iccs("d5d0").unwrap(), Some(1)));
+        let mut budget = Budget::new(Limits::new());
+        assert!(engine.parries(Move::from_iccs("a4a0").unwrap(), &threat, &mut budget, 1));
+        assert!(!engine.parries(Move::from_iccs("a4a5").unwrap(), &threat, &mut budget, 1));
+        assert!(hint.candidates.iter().all(|c| c.defends == engine.parries(c.mv, &threat, &mut budget, 1)));
+
+        // One time budget for the whole call, not one per phase.
+        engine.reset_to_startpos();
+        let time = Duration::from_millis(400);
+        let start = Instant::now();
+        engine.hint(Limits::new().time(time));
+        assert!(start.elapsed() < time * 3 / 2, "took {:?}", start.elapsed());
+
+        // The rook mates down the d-file.
+        engine.set_fen("3k5/9/9/9/9/9/9/9/9/R3K4 w").unwrap();
+        let best = engine.hint(Limits::new().depth(3)).best().cloned().unwrap();
+        assert_eq!((best.mv, best.gives_check), (Move::from_iccs("a0d0").unwrap(), true));
+    }
+}
@@ -1,34 +1,100 @@
 pub(crate) mod attacks;
+pub(crate) mod bench;
 pub(crate) mod bitboard;
 pub(crate) mod book;
+pub(crate) mod cache;
 pub(crate) mod color;
+pub(crate) mod datagen;
 pub(crate) mod engine;
+pub(crate) mod epd;
 pub(crate) mod error;
 pub(crate) mod eval;
 pub(crate) mod fen;
+pub(crate) mod game;
+pub(crate) mod hint;
 pub(crate) mod limits;
 pub(crate) mod magic;
+pub(crate) mod matchplay;
+pub(crate) mod mate;
 pub(crate) mod movegen;
 pub(crate) mod mv;
+pub(crate) mod notation;
 pub(crate) mod picker;
 pub(crate) mod piece;
 pub(crate) mod position;
+pub(crate) mod render;
+pub(crate) mod review;
 pub(crate) mod search;
 pub(crate) mod see;
+pub(crate) mod selfplay;
+pub(crate) mod skill;
+pub(crate) mod spsa;
 pub(crate) mod square;
+pub(crate) mod svg;
+pub(crate) mod tablebase;
 pub(crate) mod tt;
+pub(crate) mod tune;
 pub(crate) mod util;
 pub(crate) mod zobrist;
 
+pub use bench::BENCH_DEPTH;
+pub use bench::BENCH_FENS;
+pub use bench::BenchReport;
+pub use bench::bench;
+pub use bench::bench_with;
+pub use bitboard::BitBoard;
+pub use cache::AnalysisCache;
+pub use cache::CachedAnalysis;
 pub use color::Color;
+pub use datagen::DataGenerator;
+pub use datagen::DataRecord;
+pub use datagen::Opening;
+pub use engine::DETERMINISTIC_NODES;
 pub use engine::Engine;
 pub use engine::EngineBuilder;
+pub use engine::GameStatus;
+pub use epd::EpdRecord;
+pub use epd::EpdReport;
+pub use epd::EpdResult;
+pub use epd::run_epd;
 pub use error::ChessAIError;
+pub use eval::EvalParams;
 pub use fen::STARTING_FEN;
+pub use game::Game;
+pub use hint::Candidate;
+pub use hint::HINT_CANDIDATES;
+pub use hint::Hint;
+pub use hint::Threat;
 pub use limits::Limits;
+pub use matchplay::EngineMatch;
+pub use matchplay::MatchReport;
+pub use matchplay::Sprt;
+pub use matchplay::SprtVerdict;
+pub use mate::MateOutcome;
 pub use mv::Move;
 pub use piece::Piece;
 pub use piece::PieceType;
 pub use position::Position;
+pub use position::UndoInfo;
+pub use render::Glyphs;
+pub use render::RenderStyle;
+pub use review::AnalysedMove;
+pub use review::BLUNDER_LOSS;
+pub use review::GameAnalysis;
+pub use review::INACCURACY_LOSS;
+pub use review::MISTAKE_LOSS;
+pub use review::MoveJudgement;
+pub use search::RootLine;
+pub use search::ScoreBound;
 pub use search::SearchInfo;
+pub use search::SearchParams;
+pub use see::Threats;
+pub use spsa::SpsaTuner;
 pub use square::Square;
+pub use svg::SvgStyle;
+pub use svg::SvgTheme;
+pub use tablebase::Tablebases;
+pub use tablebase::TbProbe;
+pub use tablebase::Wdl;
+pub use tune::TexelTuner;
+pub use tune::TuningPosition;
@@ -9,16 +9,28 @@ pub struct Limits {
     pub(crate) max_depth: u8,
     pub(crate) max_time: Option<Duration>,
     pub(crate) max_nodes: Option<u64>,
+    pub(crate) contempt: Option<i32>,
+    pub(crate) multipv: u8,
+    pub(crate) mate: Option<u8>,
 }
 
 impl Default for Limits {
     fn default() -> Self {
-        Limits { max_depth: MAX_SEARCH_DEPTH, max_time: Some(Duration::from_millis(1000)), max_nodes: None }
+        Limits {
+            max_depth: MAX_SEARCH_DEPTH,
+            max_time: Some(Duration::from_millis(1000)),
+            max_nodes: None,
+            contempt: None,
+            multipv: 1,
+            mate: None,
+        }
     }
 }
 
 impl Limits {
-    pub const fn new() -> Self { Limits { max_depth: MAX_SEARCH_DEPTH, max_time: None, max_nodes: None } }
+    pub const fn new() -> Self {
+        Limits { max_depth: MAX_SEARCH_DEPTH, max_time: None, max_nodes: None, contempt: None, multipv: 1, mate: None }
+    }
 
     #[must_use]
     pub const fn depth(mut self, d: u8) -> Self {
@@ -31,4 +43,36 @@ impl Limits {
         self.max_time = Some(d);
         self
     }
+
+    /// Stop after roughly this many nodes (checked every 4096 nodes).
+    #[must_use]
+    pub const fn nodes(mut self, n: u64) -> Self {
+        self.max_nodes = Some(n);
+        self
+    }
+
+    /// Contempt for this search only, overriding `EngineBuilder::contempt`. Positive values
+    /// make the side to move avoid draws (repetitions, the natural move limit); negative
+    /// values make it seek them.
+    #[must_use]
+    pub const fn contempt(mut self, cp: i32) -> Self {
+        self.contempt = Some(cp);
+        self
+    }
+
+    /// Number of best root lines to report in `SearchInfo::lines` (MultiPV). `0` falls back to 1.
+    #[must_use]
+    pub const fn multipv(mut self, n: u8) -> Self {
+        self.multipv = if n == 0 { 1 } else { n };
+        self
+    }
+
+    /// Look for a forced mate in at most `moves` moves, or prove there is none, instead of searching
+    /// normally; the answer lands in `SearchInfo::mate`. Time and node limits bound the
+    /// solver too (nodes count tree nodes, by default up to four million).
+    #[must_use]
+    pub const fn mate(mut self, moves: u8) -> Self {
+        self.mate = Some(moves);
+        self
+    }
 }
@@ -0,0 +1,404 @@
+//! Engine-vs-engine matches with Elo estimation and a sequential probability ratio test.
+//!
+//! Both engines come from factories, so they may differ in anything an [`EngineBuilder`]
+//! sets: search or evaluation parameters, hash size, threads. Every opening is played
+//! twice with colours swapped; [`Engine::game_status`] adjudicates. Game pairs run
+//! concurrently, each worker building its own pair of engines, and the SPRT scores whole
+//! pairs (the pentanomial model), since both games of a pair share their opening.
+//!
+//! ```no_run
+//! use chessai::{Engine, EngineMatch, Limits, SearchParams, Sprt};
+//!
+//! let tuned: SearchParams = std::fs::read_to_string("tuned.search").unwrap().parse().unwrap();
+//! let report = EngineMatch::new(
+//!     move || Engine::builder().use_book(false).search_params(tuned.clone()).build(),
+//!     || Engine::builder().use_book(false).build(),
+//! )
+//! .games(20_000)
+//! .limits(Limits::new().nodes(5000))
+//! .concurrency(8)
+//! .sprt(Sprt::new(0.0, 5.0))
+//! .run(|report| eprint!("\r{report}"))
+//! .unwrap();
+//! println!("\n{report}");
+//! ```
+//!
+//! [`EngineBuilder`]: crate::EngineBuilder
+
+use std::fmt;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::engine::Engine;
+use crate::error::ChessAIError;
+use crate::fen::STARTING_FEN;
+use crate::limits::Limits;
+use crate::position::Position;
+use crate::selfplay::play_game;
+use crate::selfplay::random_opening;
+use crate::util::SplitMix64;
+
+type EngineFactory = Box<dyn Fn() -> Engine + Send + Sync>;
+
+/// Expected score of a player `elo` points stronger than its opponent.
+fn expected_score(elo: f64) -> f64 { 1.0 / (1.0 + 10f64.powf(-elo / 400.0)) }
+
+/// Inverse of [`expected_score`], for scores strictly between 0 and 1.
+fn elo_from_score(score: f64) -> f64 { 400.0 * (score / (1.0 - score)).log10() }
+
+/// Sequential probability ratio test between `H0: elo = elo0` and `H1: elo = elo1`, with
+/// false-positive rate `alpha` and false-negative rate `beta`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+/// Outcome of a finished [`Sprt`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SprtVerdict {
+    /// The first engine is no stronger than `elo0`.
+    H0,
+    /// The first engine is at least `elo1` stronger.
+    H1,
+}
+
+impl Sprt {
+    /// Test `elo0` against `elo1` with the customary 5% error rates.
+    pub fn new(elo0: f64, elo1: f64) -> Self { Sprt { elo0, elo1, alpha: 0.05, beta: 0.05 } }
+
+    #[must_use]
+    pub fn error_rates(mut self, alpha: f64, beta: f64) -> Self {
+        self.alpha = alpha;
+        self.beta = beta;
+        self
+    }
+
+    /// Log-likelihood ratio bounds: `H0` is accepted at or below the first, `H1` at or
+    /// above the second.
+    pub fn bounds(&self) -> (f64, f64) {
+        ((self.beta / (1.0 - self.alpha)).ln(), ((1.0 - self.beta) / self.alpha).ln())
+    }
+
+    /// Log-likelihood ratio of a W/D/L record of independent games, by the normal
+    /// approximation to the trinomial. Zero until the results show some variance.
+    pub fn llr(&self, wins: u32, draws: u32, losses: u32) -> f64 {
+        self.normal_llr(&[(losses, 0.0), (draws, 0.5), (wins, 1.0)])
+    }
+
+    /// Log-likelihood ratio of colour-swapped game pairs, by the normal approximation to the
+    /// pentanomial: `pairs[i]` counts the pairs in which the first engine scored `i / 2`
+    /// points. The two games of a pair share an opening and are not independent, so this
+    /// is the ratio [`EngineMatch`] tests. Zero until the results show some variance.
+    pub fn llr_pairs(&self, pairs: [u32; 5]) -> f64 {
+        let outcomes: Vec<(u32, f64)> = pairs.iter().enumerate().map(|(i, &n)| (n, i as f64 / 4.0)).collect();
+        self.normal_llr(&outcomes)
+    }
+
+    /// Normal-approximation LLR of samples given as `(count, score)` with scores in `0..=1`.
+    fn normal_llr(&self, outcomes: &[(u32, f64)]) -> f64 {
+        let n: f64 = outcomes.iter().map(|&(count, _)| count as f64).sum();
+        if n == 0.0 {
+            return 0.0;
+        }
+        let score = outcomes.iter().map(|&(count, x)| count as f64 * x).sum::<f64>() / n;
+        let variance = outcomes.iter().map(|&(count, x)| count as f64 * (x - score).powi(2)).sum::<f64>() / n;
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        let (s0, s1) = (expected_score(self.elo0), expected_score(self.elo1));
+        (s1 - s0) * (2.0 * score - s0 - s1) * n / (2.0 * variance)
+    }
+
+    /// The verdict for `llr`, or `None` while the test must go on.
+    pub fn verdict(&self, llr: f64) -> Option<SprtVerdict> {
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            Some(SprtVerdict::H1)
+        } else if llr <= lower {
+            Some(SprtVerdict::H0)
+        } else {
+            None
+        }
+    }
+}
+
+/// Running or final result of an [`EngineMatch`], from the first engine's point of view.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchReport {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Finished game pairs by the first engine's points in them: 0, ½, 1, 1½ and 2.
+    pub pairs: [u32; 5],
+    /// Log-likelihood ratio of the pairs, when an SPRT is configured.
+    pub llr: Option<f64>,
+    /// Set once the SPRT has reached a decision.
+    pub verdict: Option<SprtVerdict>,
+}
+
+impl MatchReport {
+    pub fn games(&self) -> u32 { self.wins + self.draws + self.losses }
+
+    /// Points per game, `0.0..=1.0`.
+    pub fn score(&self) -> f64 {
+        match self.games() {
+            0 => 0.5,
+            n => (self.wins as f64 + self.draws as f64 / 2.0) / n as f64,
+        }
+    }
+
+    /// Elo difference of the first engine over the second, and the half-width of its 95%
+    /// confidence interval. Both are infinite while one side has scored every point.
+    pub fn elo(&self) -> (f64, f64) {
+        let n = self.games() as f64;
+        let score = self.score();
+        if n == 0.0 || score <= 0.0 || score >= 1.0 {
+            return (if score >= 0.5 { f64::INFINITY } else { f64::NEG_INFINITY }, f64::INFINITY);
+        }
+        let variance = (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2))
+            / n;
+        let margin = 1.959_964 * (variance / n).sqrt();
+        let low = elo_from_score((score - margin).max(f64::MIN_POSITIVE));
+        let high = elo_from_score((score + margin).min(1.0 - f64::EPSILON));
+        (elo_from_score(score), (high - low) / 2.0)
+    }
+
+    fn record(&mut self, pair: [f64; 2]) {
+        for points in pair {
+            match points {
+                p if p > 0.5 => self.wins += 1,
+                p if p < 0.5 => self.losses += 1,
+                _ => self.draws += 1,
+            }
+        }
+        self.pairs[((pair[0] + pair[1]) * 2.0).round() as usize] += 1;
+    }
+}
+
+impl fmt::Display for MatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (elo, margin) = self.elo();
+        write!(
+            f,
+            "games {} W/D/L {}/{}/{} elo {elo:+.1} ± {margin:.1}",
+            self.games(),
+            self.wins,
+            self.draws,
+            self.losses
+        )?;
+        if let Some(llr) = self.llr {
+            write!(f, " llr {llr:.2}")?;
+        }
+        match self.verdict {
+            Some(SprtVerdict::H0) => write!(f, " (H0 accepted)"),
+            Some(SprtVerdict::H1) => write!(f, " (H1 accepted)"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A match between two engine configurations.
+pub struct EngineMatch {
+    engines: [EngineFactory; 2],
+    openings: Vec<String>,
+    random_plies: u8,
+    games: u32,
+    limits: Limits,
+    concurrency: usize,
+    sprt: Option<Sprt>,
+    seed: u64,
+}
+
+impl EngineMatch {
+    /// A match of `first` against `second`; reports are from `first`'s point of view.
+    pub fn new(
+        first: impl Fn() -> Engine + Send + Sync + 'static,
+        second: impl Fn() -> Engine + Send + Sync + 'static,
+    ) -> Self {
+        EngineMatch {
+            engines: [Box::new(first), Box::new(second)],
+            openings: Vec::new(),
+            random_plies: 8,
+            games: 100,
+            limits: Limits::new().nodes(10_000),
+            concurrency: 1,
+            sprt: None,
+            seed: 0x004d_4154_4348,
+        }
+    }
+
+    /// Starting positions, cycled through in order. Without any, each game pair starts
+    /// from random plies off the initial position.
+    #[must_use]
+    pub fn openings<S: Into<String>>(mut self, fens: impl IntoIterator<Item = S>) -> Self {
+        self.openings = fens.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Length of the random openings used when no suite is given.
+    #[must_use]
+    pub fn random_plies(mut self, plies: u8) -> Self {
+        self.random_plies = plies;
+        self
+    }
+
+    /// Number of games, rounded up to whole colour-swapped pairs.
+    #[must_use]
+    pub fn games(mut self, n: u32) -> Self {
+        self.games = n.max(1);
+        self
+    }
+
+    /// Search limits of every move (default: 10 000 nodes).
+    #[must_use]
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Game pairs played at once.
+    #[must_use]
+    pub fn concurrency(mut self, n: usize) -> Self {
+        self.concurrency = n.max(1);
+        self
+    }
+
+    /// Stop as soon as `sprt` reaches a verdict.
+    #[must_use]
+    pub fn sprt(mut self, sprt: Sprt) -> Self {
+        self.sprt = Some(sprt);
+        self
+    }
+
+    /// Seed of the random openings.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Play the match. `progress` sees the report after every finished game pair. Fails
+    /// up front if an opening FEN does not parse.
+    pub fn run(&self, mut progress: impl FnMut(&MatchReport)) -> Result<MatchReport, ChessAIError> {
+        for fen in &self.openings {
+            Position::from_fen(fen)?;
+        }
+        let pairs = self.games.div_ceil(2) as usize;
+        let next = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+        let mut report = MatchReport::default();
+        thread::scope(|scope| {
+            let (tx, rx) = mpsc::channel::<[f64; 2]>();
+            for _ in 0..self.concurrency.min(pairs) {
+                let tx = tx.clone();
+                let (next, stop) = (&next, &stop);
+                scope.spawn(move || {
+                    let (mut first, mut second) = ((self.engines[0])(), (self.engines[1])());
+                    loop {
+                        let pair = next.fetch_add(1, Ordering::Relaxed);
+                        if pair >= pairs || stop.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let (fen, opening) = match self.openings.len() {
+                            0 => {
+                                let mut rng = SplitMix64::new(self.seed.wrapping_add(pair as u64));
+                                (STARTING_FEN, random_opening(&mut rng, self.random_plies))
+                            }
+                            n => (self.openings[pair % n].as_str(), Vec::new()),
+                        };
+                        let play = |red: &mut Engine, black: &mut Engine| {
+                            play_game(red, black, fen, &opening, self.limits, |_, _, _| {})
+                                .expect("openings are validated")
+                                .red_score()
+                                .unwrap_or(0.5)
+                        };
+                        let as_red = play(&mut first, &mut second);
+                        let as_black = 1.0 - play(&mut second, &mut first);
+                        if tx.send([as_red, as_black]).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(tx);
+            for pair in rx {
+                report.record(pair);
+                if let Some(sprt) = self.sprt {
+                    let llr = sprt.llr_pairs(report.pairs);
+                    report.llr = Some(llr);
+                    if report.verdict.is_none() {
+                        report.verdict = sprt.verdict(llr);
+                        if report.verdict.is_some() {
+                            stop.store(true, Ordering::Relaxed);
+                        }
+                    }
+                }
+                progress(&report);
+            }
+        });
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elo_and_llr_follow_the_record() {
+        let even = MatchReport { wins: 30, draws: 40, losses: 30, ..Default::default() };
+        assert_eq!(even.elo().0, 0.0);
+        assert!(even.elo().1 > 0.0);
+
+        let ahead = MatchReport { wins: 50, draws: 20, losses: 30, ..Default::default() };
+        let (elo, margin) = ahead.elo();
+        assert!((elo - 70.44).abs() < 0.01, "elo {elo}");
+        assert!(elo - margin > 0.0 && margin < elo);
+
+        let sprt = Sprt::new(0.0, 10.0);
+        assert!(sprt.llr(500, 200, 300) > sprt.bounds().1);
+        assert_eq!(sprt.verdict(sprt.llr(500, 200, 300)), Some(SprtVerdict::H1));
+        assert_eq!(sprt.verdict(sprt.llr(300, 200, 500)), Some(SprtVerdict::H0));
+        assert_eq!(sprt.verdict(sprt.llr(3, 2, 3)), None);
+
+        // Colour-swapped pairs that split the points show less noise than the same games
+        // counted one by one, so the pair model is more confident.
+        assert_eq!(sprt.llr_pairs([0; 5]), 0.0);
+        assert_eq!(sprt.verdict(sprt.llr_pairs([10, 50, 200, 150, 90])), Some(SprtVerdict::H1));
+        assert_eq!(sprt.verdict(sprt.llr_pairs([90, 150, 200, 50, 10])), Some(SprtVerdict::H0));
+        let split = sprt.llr_pairs([0, 0, 300, 100, 0]);
+        assert!(split > sprt.llr(100, 700, 0), "{split}");
+
+        let mut report = MatchReport::default();
+        report.record([1.0, 0.5]);
+        report.record([0.0, 1.0]);
+        assert_eq!((report.wins, report.draws, report.losses, report.pairs), (2, 1, 1, [0, 0, 1, 1, 0]));
+    }
+
+    #[test]
+    fn stronger_engine_wins_the_match() {
+        let mut games = 0;
+        let report = EngineMatch::new(
+            || Engine::builder().use_book(false).hash_size(1).build(),
+            || Engine::builder().use_book(false).hash_size(1).skill_level(0).seed(5).build(),
+        )
+        .games(4)
+        .limits(Limits::new().depth(2))
+        .concurrency(2)
+        .run(|report| games = report.games())
+        .unwrap();
+        assert_eq!((report.games(), games), (4, 4));
+        assert!(report.wins > report.losses, "{report}");
+
+        let bad = EngineMatch::new(Engine::default, Engine::default).openings(["not a fen"]);
+        assert!(bad.run(|_| {}).is_err());
+    }
+}
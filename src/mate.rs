@@ -0,0 +1,258 @@
+//! Mate solver behind [`Limits::mate`](crate::Limits::mate): proof-number search over the
+//! AND/OR tree of the attacker (the side to move at the root) and the defender. A
+//! defender without a legal move has lost — in xiangqi that covers stalemate as well as
+//! checkmate. Every legal move is tried on both sides, so a failed search is a real
+//! proof; checks still come first in practice, since a defender reply count seeds each
+//! proof number and a check leaves few replies.
+//!
+//! Mates in `1..=n` moves are tried in turn, so the first proof is also the shortest
+//! mate. Repeating a position never counts as progress for the attacker (perpetual check
+//! is forbidden), which keeps every proof sound under the repetition rules.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use crate::limits::Limits;
+use crate::movegen::MoveList;
+use crate::movegen::generate_legal;
+use crate::mv::Move;
+use crate::position::Position;
+
+/// Proof or disproof number of a settled node.
+const INF: u32 = u32::MAX / 2;
+
+/// Tree-node budget when `Limits` sets none (about 64 MiB of tree).
+const DEFAULT_MATE_NODES: u64 = 4_000_000;
+
+/// Result of a mate search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MateOutcome {
+    /// A forced mate: the attacker's moves interleaved with the defender's longest
+    /// resistance, ending in mate.
+    Mate(Vec<Move>),
+    /// Proof that no mate exists within the requested number of moves.
+    NoMate,
+    /// The node or time budget ran out, or the search was stopped, before a proof.
+    Unknown,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Node {
+    mv: Move,
+    pn: u32,
+    dn: u32,
+    /// Children occupy `first..first + count` in the arena once expanded.
+    first: u32,
+    count: u16,
+}
+
+struct Solver<'a> {
+    pos: &'a mut Position,
+    nodes: Vec<Node>,
+    /// Keys of the positions from the root to the node being expanded.
+    path: Vec<u64>,
+    created: u64,
+    budget: u64,
+    deadline: Option<Instant>,
+    stop: &'a AtomicBool,
+}
+
+/// Look for a mate in at most `moves` attacker moves. Returns the outcome and the number of
+/// tree nodes created. `pos` is restored before returning.
+pub(crate) fn solve(pos: &mut Position, moves: u8, limits: &Limits, stop: &AtomicBool) -> (MateOutcome, u64) {
+    let mut solver = Solver {
+        pos,
+        nodes: Vec::new(),
+        path: Vec::new(),
+        created: 0,
+        budget: limits.max_nodes.unwrap_or(DEFAULT_MATE_NODES),
+        deadline: limits.max_time.map(|t| Instant::now() + t),
+        stop,
+    };
+    for k in 1..=moves as u32 {
+        match solver.prove(2 * k - 1) {
+            Some(true) => return (MateOutcome::Mate(solver.line()), solver.created),
+            Some(false) => {}
+            None => return (MateOutcome::Unknown, solver.created),
+        }
+    }
+    (MateOutcome::NoMate, solver.created)
+}
+
+impl Solver<'_> {
+    /// Run proof-number search to a horizon of `plies`. `Some(true)` if the root is proven,
+    /// `Some(false)` if disproven, `None` if the budget ran out first.
+    fn prove(&mut self, plies: u32) -> Option<bool> {
+        self.nodes.clear();
+        self.nodes.push(Node { mv: Move::NULL, pn: 1, dn: 1, first: 0, count: 0 });
+        self.path.clear();
+        self.path.push(self.pos.zobrist_key());
+        while self.nodes[0].pn != 0 && self.nodes[0].dn != 0 {
+            if self.out_of_budget() {
+                return None;
+            }
+            self.descend(0, plies, true);
+        }
+        Some(self.nodes[0].pn == 0)
+    }
+
+    fn out_of_budget(&self) -> bool {
+        self.created >= self.budget
+            || self.stop.load(Ordering::Relaxed)
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Walk to the most-proving leaf below `idx`, expand it, and refresh the proof numbers
+    /// on the way back up.
+    fn descend(&mut self, idx: usize, remaining: u32, attacker: bool) {
+        let node = self.nodes[idx];
+        if node.count == 0 {
+            self.expand(idx, remaining, attacker);
+        } else {
+            let children = node.first as usize..node.first as usize + node.count as usize;
+            let child = if attacker {
+                children.min_by_key(|&c| self.nodes[c].pn)
+            } else {
+                children.min_by_key(|&c| self.nodes[c].dn)
+            }
+            .expect("expanded node has children");
+            let mv = self.nodes[child].mv;
+            let undo = self.pos.make_move(mv);
+            self.path.push(self.pos.zobrist_key());
+            self.descend(child, remaining - 1, !attacker);
+            self.path.pop();
+            self.pos.undo_move(mv, undo);
+        }
+        self.update(idx, attacker);
+    }
+
+    fn expand(&mut self, idx: usize, remaining: u32, attacker: bool) {
+        let mut legal = MoveList::new();
+        generate_legal(self.pos, &mut legal);
+        let first = self.nodes.len();
+        for &mv in legal.iter() {
+            let undo = self.pos.make_move(mv);
+            let (pn, dn) = self.evaluate(remaining - 1, !attacker);
+            self.pos.undo_move(mv, undo);
+            self.nodes.push(Node { mv, pn, dn, first: 0, count: 0 });
+        }
+        let count = self.nodes.len() - first;
+        self.created += count as u64;
+        self.nodes[idx].first = first as u32;
+        self.nodes[idx].count = count as u16;
+    }
+
+    /// Initial proof numbers for a freshly created node, `remaining` plies from the horizon.
+    fn evaluate(&self, remaining: u32, attacker: bool) -> (u32, u32) {
+        if self.path.contains(&self.pos.zobrist_key()) {
+            return (INF, 0);
+        }
+        if attacker {
+            return (1, 1);
+        }
+        let mut replies = MoveList::new();
+        generate_legal(self.pos, &mut replies);
+        if replies.len() == 0 {
+            (0, INF)
+        } else if remaining == 0 {
+            (INF, 0)
+        } else {
+            // Every reply must be refuted: more replies, harder to prove.
+            (replies.len() as u32, 1)
+        }
+    }
+
+    /// OR node: proven by any child, disproven by all. AND node: the reverse. A node
+    /// without children is settled — an attacker without a move has lost; a mated
+    /// defender is settled on creation and never expanded.
+    fn update(&mut self, idx: usize, attacker: bool) {
+        let node = self.nodes[idx];
+        let children = &self.nodes[node.first as usize..node.first as usize + node.count as usize];
+        let min = |f: fn(&Node) -> u32| children.iter().map(f).min().unwrap_or(INF);
+        let sum = |f: fn(&Node) -> u32| children.iter().map(f).fold(0u32, |acc, n| acc.saturating_add(n).min(INF));
+        let (pn, dn) = if attacker { (min(|n| n.pn), sum(|n| n.dn)) } else { (sum(|n| n.pn), min(|n| n.dn)) };
+        self.nodes[idx].pn = pn;
+        self.nodes[idx].dn = dn;
+    }
+
+    /// Plies to mate below a proven node, with the attacker taking the fastest proven
+    /// move and the defender the slowest reply.
+    fn mate_length(&self, idx: usize, attacker: bool) -> u32 {
+        let node = self.nodes[idx];
+        let children = node.first as usize..node.first as usize + node.count as usize;
+        let lengths = children.filter(|&c| self.nodes[c].pn == 0).map(|c| 1 + self.mate_length(c, !attacker));
+        if attacker { lengths.min().unwrap_or(0) } else { lengths.max().unwrap_or(0) }
+    }
+
+    /// The principal line of a proven root.
+    fn line(&self) -> Vec<Move> {
+        let mut line = Vec::new();
+        let (mut idx, mut attacker) = (0, true);
+        loop {
+            let node = self.nodes[idx];
+            let children = node.first as usize..node.first as usize + node.count as usize;
+            let proven = children.filter(|&c| self.nodes[c].pn == 0);
+            let next = if attacker {
+                proven.min_by_key(|&c| self.mate_length(c, false))
+            } else {
+                proven.max_by_key(|&c| self.mate_length(c, true))
+            };
+            let Some(next) = next else { return line };
+            line.push(self.nodes[next].mv);
+            (idx, attacker) = (next, !attacker);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    fn run(fen: &str, moves: u8) -> MateOutcome {
+        let mut pos = Position::from_fen(fen).unwrap();
+        let (outcome, _) = solve(&mut pos, moves, &Limits::new(), &AtomicBool::new(false));
+        assert_eq!(pos.to_fen(), Position::from_fen(fen).unwrap().to_fen());
+        outcome
+    }
+
+    fn assert_mates(fen: &str, line: &[Move]) {
+        let mut pos = Position::from_fen(fen).unwrap();
+        for &mv in line {
+            assert!(pos.is_legal(mv));
+            pos.make_move(mv);
+        }
+        let mut replies = MoveList::new();
+        generate_legal(&pos, &mut replies);
+        assert_eq!(replies.len(), 0);
+        assert_eq!(pos.side_to_move(), Color::Black);
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        // Rook to d0 mates: the black king cannot step onto the e-file facing the red king.
+        let fen = "3k5/9/9/9/9/9/9/9/9/R3K4 w";
+        let MateOutcome::Mate(line) = run(fen, 3) else { panic!("mate expected") };
+        assert_eq!(line.len(), 1);
+        assert_mates(fen, &line);
+    }
+
+    #[test]
+    fn finds_shortest_mate_in_two() {
+        let fen = "3k5/9/9/9/9/9/9/9/4C4/R3K4 w";
+        assert_eq!(run(fen, 1), MateOutcome::NoMate);
+        let MateOutcome::Mate(line) = run(fen, 3) else { panic!("mate expected") };
+        assert_eq!(line.len(), 3);
+        assert_mates(fen, &line);
+    }
+
+    #[test]
+    fn proves_absence_and_respects_budget() {
+        assert_eq!(run(crate::fen::STARTING_FEN, 2), MateOutcome::NoMate);
+        let mut pos = Position::from_fen("3k5/9/9/9/9/9/9/9/4C4/R3K4 w").unwrap();
+        let (outcome, nodes) = solve(&mut pos, 3, &Limits::new().nodes(1), &AtomicBool::new(false));
+        assert_eq!(outcome, MateOutcome::Unknown);
+        assert!(nodes >= 1);
+    }
+}
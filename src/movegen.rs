@@ -2,15 +2,18 @@ use std::ops::Index;
 
 use crate::attacks::ADVISOR_ATTACKS;
 use crate::attacks::KING_ATTACKS;
+use crate::attacks::KNIGHT_RAYS;
 use crate::attacks::PAWN_ATTACKS;
 use crate::attacks::bishop_attacks;
 use crate::attacks::knight_attacks;
 use crate::bitboard::BitBoard;
 use crate::bitboard::HOME_HALVES;
+use crate::bitboard::between;
 use crate::magic::cannon_attacks;
 use crate::magic::rook_attacks;
 use crate::mv::Move;
 use crate::piece::PieceType;
+use crate::position::CheckInfo;
 use crate::position::Position;
 use crate::square::Square;
 
@@ -215,6 +218,107 @@ pub(crate) fn generate_quiets(pos: &Position, out: &mut MoveList) {
     let _ = own; // unused — every "& empties" implicitly excludes own pieces
 }
 
+/// Pseudo-legal non-captures that give check, including discovered checks (a rook line or
+/// knight leg vacated, a cannon screen added or removed). Used by the first quiescence
+/// plies so quiet mating attacks stay on the horizon.
+pub(crate) fn generate_quiet_checks(pos: &Position, out: &mut MoveList) {
+    out.clear();
+    let Some(king) = pos.king_square(pos.side_to_move().flip()) else { return };
+    let mut quiets = MoveList::new();
+    generate_quiets(pos, &mut quiets);
+    for &mv in quiets.iter() {
+        if may_check(king, mv) && pos.gives_check(mv) {
+            out.push(mv);
+        }
+    }
+}
+
+/// Cheap geometric pre-filter for `gives_check`: a checking move either touches the
+/// king's rank or file (line pieces, screens, discoveries), lands within knight or pawn
+/// reach of it, or vacates a square next to it (a knight leg).
+#[inline]
+fn may_check(king: Square, mv: Move) -> bool {
+    let on_line = |sq: Square| sq.rank() == king.rank() || sq.file() == king.file();
+    let distance = |sq: Square| sq.rank().abs_diff(king.rank()).max(sq.file().abs_diff(king.file()));
+    on_line(mv.src()) || on_line(mv.dst()) || distance(mv.dst()) <= 2 || distance(mv.src()) <= 1
+}
+
+// ======================================================================
+// Legal generation
+// ======================================================================
+
+/// Fully legal moves for the side to move. Out of check, pseudo-legal moves are filtered
+/// through the position's pins and cannon lanes; in check this defers to
+/// [`generate_evasions`].
+pub(crate) fn generate_legal(pos: &Position, out: &mut MoveList) {
+    let info = pos.check_info();
+    if info.checkers.any() {
+        generate_evasions_with(pos, &info, out);
+        return;
+    }
+    let mut pseudo = MoveList::new();
+    generate_pseudo(pos, &mut pseudo);
+    out.clear();
+    for &mv in pseudo.iter() {
+        if pos.legal(mv, &info) {
+            out.push(mv);
+        }
+    }
+}
+
+/// Legal replies to a check: king steps, captures of a checker, interpositions on a rook,
+/// king or cannon line, a second piece dropped between a cannon and its screen, the screen
+/// stepping off the line, and blocks on a checking knight's leg. With no check this
+/// produces nothing.
+pub(crate) fn generate_evasions(pos: &Position, out: &mut MoveList) {
+    let info = pos.check_info();
+    generate_evasions_with(pos, &info, out);
+}
+
+fn generate_evasions_with(pos: &Position, info: &CheckInfo, out: &mut MoveList) {
+    out.clear();
+    let us = pos.side_to_move();
+    let Some(king) = pos.king_square(us) else { return };
+    if info.checkers.is_empty() {
+        return;
+    }
+
+    // Every non-king evasion lands on a checker or a square that cuts its attack, or moves
+    // a cannon's screen away. Collect those for all checkers; legality settles the rest
+    // (including double checks a single move cannot answer).
+    let occ = pos.occupancy();
+    let mut targets = info.checkers;
+    let mut screens = BitBoard::EMPTY;
+    for sq in info.checkers {
+        let kind = pos.piece_at(sq).map(|p| p.kind());
+        match kind {
+            Some(PieceType::Rook | PieceType::King) => targets |= between(king, sq),
+            Some(PieceType::Cannon) => {
+                let path = between(king, sq);
+                targets |= path - occ;
+                screens |= path & occ;
+            }
+            Some(PieceType::Knight) => {
+                for entry in KNIGHT_RAYS[sq.raw() as usize].iter() {
+                    if entry.destinations.has(king) {
+                        targets |= entry.blocker;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut pseudo = MoveList::new();
+    generate_pseudo(pos, &mut pseudo);
+    for &mv in pseudo.iter() {
+        let candidate = mv.src() == king || targets.has(mv.dst()) || screens.has(mv.src());
+        if candidate && pos.legal(mv, info) {
+            out.push(mv);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,4 +385,115 @@ mod tests {
             assert!(pos.piece_at(mv.dst()).is_none(), "quiet move {mv} landed on a piece");
         }
     }
+
+    /// The old path: pseudo-legal moves kept if the mover's king survives make/undo.
+    fn reference_legal(pos: &mut Position) -> Vec<Move> {
+        let us = pos.side_to_move();
+        let mut pseudo = MoveList::new();
+        generate_pseudo(pos, &mut pseudo);
+        let mut out = Vec::new();
+        for &mv in pseudo.iter() {
+            let undo = pos.make_move(mv);
+            if !pos.is_in_check(us) {
+                out.push(mv);
+            }
+            pos.undo_move(mv, undo);
+        }
+        out.sort_by_key(|m| m.raw());
+        out
+    }
+
+    /// Perft over `generate_legal`, asserting at every node that it matches the reference
+    /// move set (and that evasions match it whenever the side to move is in check).
+    fn perft(pos: &mut Position, depth: u32) -> u64 {
+        let mut legal = MoveList::new();
+        generate_legal(pos, &mut legal);
+        let mut ours: Vec<Move> = legal.as_slice().to_vec();
+        ours.sort_by_key(|m| m.raw());
+        assert_eq!(ours, reference_legal(pos), "fen={}", pos.to_fen());
+
+        let mut evasions = MoveList::new();
+        generate_evasions(pos, &mut evasions);
+        if pos.is_in_check(pos.side_to_move()) {
+            assert_eq!(evasions.len(), ours.len(), "fen={}", pos.to_fen());
+        } else {
+            assert_eq!(evasions.len(), 0, "fen={}", pos.to_fen());
+        }
+
+        if depth == 1 {
+            return ours.len() as u64;
+        }
+        let mut nodes = 0;
+        for mv in ours {
+            let undo = pos.make_move(mv);
+            nodes += perft(pos, depth - 1);
+            pos.undo_move(mv, undo);
+        }
+        nodes
+    }
+
+    #[test]
+    fn quiet_checks_match_make_and_test() {
+        let fens = [
+            STARTING_FEN,
+            "r1bakabr1/9/1cn1c1n2/p1p3p1p/4p4/2P6/P3P1P1P/2N1C1N2/9/R1BAKABR1 w",
+            // Red cannon behind its knight and a black pawn: knight moves discover a check.
+            "4k4/9/9/9/4p4/9/4N4/9/4C4/3K5 w",
+            // Red cannon with no screen: any red piece stepping onto the e-file screens it.
+            "4k4/9/9/9/9/9/9/R8/4C4/3K5 w",
+        ];
+        for fen in fens {
+            let mut pos = Position::from_fen(fen).unwrap();
+            let mut checks = MoveList::new();
+            generate_quiet_checks(&pos, &mut checks);
+            let mut quiets = MoveList::new();
+            generate_quiets(&pos, &mut quiets);
+            let them = pos.side_to_move().flip();
+            let mut expected = Vec::new();
+            for &mv in quiets.iter() {
+                let undo = pos.make_move(mv);
+                if pos.is_in_check(them) {
+                    expected.push(mv);
+                }
+                pos.undo_move(mv, undo);
+            }
+            assert_eq!(
+                checks.as_slice(),
+                expected.as_slice(),
+                "fen={fen} got={:?} want={:?}",
+                checks.iter().map(|m| m.to_iccs()).collect::<Vec<_>>(),
+                expected.iter().map(|m| m.to_iccs()).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn startpos_perft_matches_known_counts() {
+        let mut pos = Position::from_fen(STARTING_FEN).unwrap();
+        assert_eq!(perft(&mut pos, 1), 44);
+        assert_eq!(perft(&mut pos, 2), 1_920);
+        assert_eq!(perft(&mut pos, 3), 79_666);
+    }
+
+    #[test]
+    fn legal_generator_agrees_with_make_undo() {
+        let fens = [
+            // Middlegame with open cannon files.
+            "r1bakabr1/9/1cn1c1n2/p1p3p1p/4p4/2P6/P3P1P1P/2N1C1N2/9/R1BAKABR1 w",
+            // Flying general: the lone rook between the kings is checking black.
+            "4k4/9/9/9/9/4R4/9/9/9/4K4 b",
+            // Knight check; the advisor can capture the knight or block its leg.
+            "4k4/9/9/9/9/9/9/3n5/4A4/4K1R2 w",
+            // Cannon check through a pawn screen; the rook can add a second screen.
+            "4k4/9/9/9/4c4/9/R8/4P4/9/4K4 w",
+            // Open cannon lane: the rook must not land between cannon and king.
+            "3k5/9/9/9/4c4/9/9/9/R8/4K4 w",
+            // Cannon with two screens: either red piece leaving the file gives check.
+            "3k5/9/9/9/4c4/4N4/9/4P4/9/4K4 w",
+        ];
+        for fen in fens {
+            let mut pos = Position::from_fen(fen).unwrap();
+            perft(&mut pos, 3);
+        }
+    }
 }
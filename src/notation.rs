@@ -0,0 +1,185 @@
+//! WXF move notation, e.g. `C2.5`, `H8+7`, `R1-2`.
+//!
+//! A move is the piece letter (`K A E H R C P`), the file it stands on counted from the
+//! mover's right (`1..=9`), a direction (`+` forward, `-` backward, `.` sideways) and
+//! either the destination file or, for a straight forward/backward move of a king, rook,
+//! cannon or pawn, the number of ranks travelled. When two pieces of a kind share a file the
+//! file is replaced by `+` (front) or `-` (rear): `R+.5`, `H-+7`. Advisors and elephants
+//! never need this, their destination tells them apart.
+//!
+//! Pawns can crowd a file further. With three or more on one file, or two on each of two
+//! files, the letter gives way to the pawn's place on its file and the file is kept:
+//! `+7.6` / `-7.6` for two, `17+1`, `27+1`, ... counted from the front for more.
+
+use std::cmp::Reverse;
+
+use crate::color::Color;
+use crate::error::ChessAIError;
+use crate::movegen::MoveList;
+use crate::movegen::generate_legal;
+use crate::mv::Move;
+use crate::piece::PieceType;
+use crate::position::Position;
+use crate::square::Square;
+
+/// File number `1..=9` counted from `color`'s right.
+fn wxf_file(sq: Square, color: Color) -> u8 {
+    match color {
+        Color::Red => 9 - sq.file(),
+        Color::Black => sq.file() + 1,
+    }
+}
+
+fn wxf_letter(kind: PieceType) -> char {
+    match kind {
+        PieceType::Bishop => 'E',
+        PieceType::Knight => 'H',
+        kind => kind.fen_char(),
+    }
+}
+
+impl Position {
+    /// The WXF form of `mv`, which must move a piece of the side to move.
+    pub fn move_to_wxf(&self, mv: Move) -> String {
+        let piece = self.piece_at(mv.src()).expect("move starts on a piece");
+        let (us, kind) = (piece.color(), piece.kind());
+        let (src, dst) = (mv.src(), mv.dst());
+        let forward = |rank: u8| if us == Color::Red { rank as i8 } else { -(rank as i8) };
+
+        // Our pieces of this kind on `file`, front first.
+        let on_file = |file: u8| {
+            let mut squares: Vec<Square> = (0..10)
+                .filter_map(|rank| Square::from_rank_file(rank, file))
+                .filter(|&sq| self.piece_at(sq) == Some(piece))
+                .collect();
+            squares.sort_by_key(|sq| Reverse(forward(sq.rank())));
+            squares
+        };
+        let file_mates = match kind {
+            PieceType::Advisor | PieceType::Bishop => vec![src],
+            _ => on_file(src.file()),
+        };
+        let nth = file_mates.iter().position(|&sq| sq == src).unwrap_or(0);
+        let mark = match (file_mates.len(), nth) {
+            (2, 0) => '+',
+            (2, _) => '-',
+            (_, nth) => (b'1' + nth as u8) as char,
+        };
+        let mut out = String::with_capacity(4);
+        if file_mates.len() == 1 {
+            out.push(wxf_letter(kind));
+            out.push((b'0' + wxf_file(src, us)) as char);
+        } else if kind == PieceType::Pawn
+            && (file_mates.len() > 2 || (0..9).any(|file| file != src.file() && on_file(file).len() > 1))
+        {
+            out.push(mark);
+            out.push((b'0' + wxf_file(src, us)) as char);
+        } else {
+            out.push(wxf_letter(kind));
+            out.push(mark);
+        }
+
+        let climb = forward(dst.rank()) - forward(src.rank());
+        out.push(match climb {
+            0 => '.',
+            c if c > 0 => '+',
+            _ => '-',
+        });
+        let straight_mover = matches!(kind, PieceType::King | PieceType::Rook | PieceType::Cannon | PieceType::Pawn);
+        let arg = if straight_mover && climb != 0 { climb.unsigned_abs() } else { wxf_file(dst, us) };
+        out.push((b'0' + arg) as char);
+        out
+    }
+
+    /// Parse a WXF move for the side to move. Accepts `=` for `.`, `B` / `N` for `E` / `H`
+    /// and the tandem mark before the letter (`+R.5`), as well as the crowded-pawn forms (`17+1`).
+    pub fn parse_wxf(&self, s: &str) -> Result<Move, ChessAIError> {
+        let bad = || ChessAIError::BadWxfMove(s.to_string());
+        let mut chars: Vec<char> = s.trim().chars().collect();
+        if chars.len() != 4 {
+            return Err(bad());
+        }
+        if matches!(chars[0], '+' | '-') && chars[1].is_ascii_alphabetic() {
+            chars.swap(0, 1);
+        }
+        if chars[0].is_ascii_alphabetic() {
+            let kind = PieceType::from_fen_char(chars[0]).ok_or_else(bad)?;
+            chars[0] = wxf_letter(kind);
+        }
+        if chars[2] == '=' {
+            chars[2] = '.';
+        }
+        let wanted: String = chars.into_iter().collect();
+        let mut legal = MoveList::new();
+        generate_legal(self, &mut legal);
+        legal.iter().copied().find(|&mv| self.move_to_wxf(mv) == wanted).ok_or_else(bad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::STARTING_FEN;
+
+    fn wxf(fen: &str, iccs: &str) -> String {
+        Position::from_fen(fen).unwrap().move_to_wxf(Move::from_iccs(iccs).unwrap())
+    }
+
+    #[test]
+    fn formats_and_parses_wxf() {
+        assert_eq!(wxf(STARTING_FEN, "h2e2"), "C2.5");
+        assert_eq!(wxf(STARTING_FEN, "h0g2"), "H2+3");
+        assert_eq!(wxf(STARTING_FEN, "i0i1"), "R1+1");
+        assert_eq!(wxf(STARTING_FEN, "c0e2"), "E7+5");
+        assert_eq!(wxf(STARTING_FEN, "f0e1"), "A4+5");
+        assert_eq!(wxf(STARTING_FEN, "g3g4"), "P3+1");
+        let black = "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR b";
+        assert_eq!(wxf(black, "h7e7"), "C8.5");
+        assert_eq!(wxf(black, "b9c7"), "H2+3");
+        // Tandem rooks on file a: front one moves sideways, rear one backwards.
+        let tandem = "4k4/9/9/9/9/R8/9/9/R8/4K4 w";
+        assert_eq!(wxf(tandem, "a4e4"), "R+.5");
+        assert_eq!(wxf(tandem, "a1a0"), "R--1");
+        // Tandem horses are told apart the same way.
+        let horses = "3k5/9/9/9/9/4N4/9/4N4/9/3K5 w";
+        assert_eq!(wxf(horses, "e4d6"), "H++6");
+        assert_eq!(wxf(horses, "e2d4"), "H-+6");
+        // Three pawns on a file are numbered from the front; so are pairs on two files.
+        let three = "3k5/9/2P6/2P6/2P6/9/9/9/9/4K4 w";
+        assert_eq!(wxf(three, "c7c8"), "17+1");
+        assert_eq!(wxf(three, "c6b6"), "27.8");
+        assert_eq!(wxf(three, "c5d5"), "37.6");
+        let pairs = "3k5/9/9/2P3P2/2P3P2/9/9/9/9/4K4 w";
+        assert_eq!(wxf(pairs, "c6c7"), "+7+1");
+        assert_eq!(wxf(pairs, "g5f5"), "-3.4");
+
+        for (fen, text, iccs) in [
+            (STARTING_FEN, "C2.5", "h2e2"),
+            (STARTING_FEN, "C2=5", "h2e2"),
+            (STARTING_FEN, "N2+3", "h0g2"),
+            (tandem, "+R.5", "a4e4"),
+            (black, "h2+3", "b9c7"),
+            (horses, "H++6", "e4d6"),
+            (horses, "+H+6", "e4d6"),
+            (horses, "H-+6", "e2d4"),
+            (three, "27.8", "c6b6"),
+            (three, "37=6", "c5d5"),
+            (pairs, "+7+1", "c6c7"),
+            (pairs, "-3.4", "g5f5"),
+        ] {
+            assert_eq!(Position::from_fen(fen).unwrap().parse_wxf(text).unwrap(), Move::from_iccs(iccs).unwrap());
+        }
+        // Every legal move round-trips, however crowded the files.
+        for fen in [STARTING_FEN, tandem, horses, three, pairs] {
+            let pos = Position::from_fen(fen).unwrap();
+            let mut legal = MoveList::new();
+            generate_legal(&pos, &mut legal);
+            for &mv in legal.iter() {
+                assert_eq!(pos.parse_wxf(&pos.move_to_wxf(mv)).unwrap(), mv, "{fen}: {}", pos.move_to_wxf(mv));
+            }
+        }
+        let start = Position::from_fen(STARTING_FEN).unwrap();
+        assert!(start.parse_wxf("C2.9").is_err());
+        assert!(start.parse_wxf("X2.5").is_err());
+    }
+}
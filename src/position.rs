@@ -1,24 +1,54 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::attacks::KNIGHT_RAYS;
+use crate::attacks::PAWN_ATTACKS;
 use crate::attacks::bishop_attacks;
 use crate::attacks::knight_attacks;
 use crate::bitboard::BitBoard;
+use crate::bitboard::between;
 use crate::color::Color;
-use crate::eval::psq_value;
+use crate::eval::DEFAULT_EVAL_PARAMS;
+use crate::eval::EvalParams;
 use crate::magic::cannon_attacks;
 use crate::magic::rook_attacks;
+use crate::movegen::MoveList;
+use crate::movegen::generate_pseudo;
 use crate::mv::Move;
 use crate::piece::Piece;
 use crate::piece::PieceType;
+use crate::see;
+use crate::see::Threats;
 use crate::square::Square;
 use crate::zobrist::ZOBRIST;
 
-/// Snapshot needed to undo a single ply.
+/// Snapshot needed to undo a single ply. Returned by [`Position::try_make_move`] and handed
+/// back to [`Position::try_undo_move`]; its contents are private.
 #[derive(Copy, Clone, Debug, Default)]
-pub(crate) struct UndoInfo {
+pub struct UndoInfo {
     pub(crate) captured: Option<Piece>,
     pub(crate) key_before: u64,
     pub(crate) lock_before: u32,
 }
 
+/// King-safety summary for the side to move, computed once per node so that most moves can
+/// be proven legal without touching the board.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct CheckInfo {
+    /// Enemy pieces attacking the king; the enemy king counts under the flying-general rule.
+    pub(crate) checkers: BitBoard,
+    /// Pieces of either colour whose departure would expose the king to a rook, king or
+    /// knight: a lone piece between it and an enemy rook or king, and the occupied leg of
+    /// an enemy knight.
+    pub(crate) pins: BitBoard,
+    /// Both pieces between the king and an enemy cannon with two in the way; removing
+    /// either one leaves the cannon a single screen.
+    pub(crate) screens: BitBoard,
+    /// Empty squares between the king and an enemy cannon on an open line. A piece landing
+    /// on one becomes the cannon's screen.
+    pub(crate) cannon_lanes: BitBoard,
+}
+
 /// Snapshot for a null-move (pass).
 #[derive(Copy, Clone, Debug, Default)]
 pub(crate) struct NullUndo {
@@ -26,7 +56,7 @@ pub(crate) struct NullUndo {
     pub(crate) lock_before: u32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Position {
     /// Occupancy per color.
     color_bb: [BitBoard; 2],
@@ -49,6 +79,10 @@ pub struct Position {
     material: [i32; 2],
     /// Incremental piece-square score per color (red's perspective = `psq[0] - psq[1]`).
     psq: [i32; 2],
+
+    /// Tables the incremental material / PST scores are computed from; `None` stands for
+    /// the compile-time defaults.
+    params: Option<Arc<EvalParams>>,
 }
 
 const EMPTY_MAILBOX_SLOT: u8 = u8::MAX;
@@ -57,6 +91,23 @@ impl Default for Position {
     fn default() -> Self { Position::empty() }
 }
 
+/// Every field but the evaluation parameters, which would drown the board in table rows.
+impl fmt::Debug for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Position")
+            .field("color_bb", &self.color_bb)
+            .field("piece_bb", &self.piece_bb)
+            .field("occ", &self.occ)
+            .field("mailbox", &self.mailbox)
+            .field("stm", &self.stm)
+            .field("key", &self.key)
+            .field("lock", &self.lock)
+            .field("material", &self.material)
+            .field("psq", &self.psq)
+            .finish_non_exhaustive()
+    }
+}
+
 impl Position {
     pub(crate) const fn empty() -> Self {
         Position {
@@ -69,6 +120,7 @@ impl Position {
             lock: 0,
             material: [0, 0],
             psq: [0, 0],
+            params: None,
         }
     }
 
@@ -102,6 +154,25 @@ impl Position {
     #[inline]
     pub(crate) fn zobrist_lock(&self) -> u32 { self.lock }
 
+    /// Evaluation parameters backing the incremental scores.
+    #[inline]
+    pub fn eval_params(&self) -> &EvalParams { self.params.as_deref().unwrap_or(&DEFAULT_EVAL_PARAMS) }
+
+    /// Swap in a different parameter set and recompute the incremental material / PST
+    /// scores from scratch.
+    pub(crate) fn set_eval_params(&mut self, params: Arc<EvalParams>) {
+        self.material = [0, 0];
+        self.psq = [0, 0];
+        for raw in 0..Square::COUNT as u8 {
+            let sq = Square::new_unchecked(raw);
+            if let Some(piece) = self.piece_at(sq) {
+                self.material[piece.color().index()] += params.piece_value(piece.kind());
+                self.psq[piece.color().index()] += params.psq(piece, sq) as i32;
+            }
+        }
+        self.params = Some(params);
+    }
+
     #[inline]
     pub fn king_square(&self, color: Color) -> Option<Square> {
         let bb = self.pieces(color, PieceType::King);
@@ -124,8 +195,9 @@ impl Position {
         self.key ^= z.key_piece[piece.index()][sq.raw() as usize];
         self.lock ^= z.lock_piece[piece.index()][sq.raw() as usize];
 
-        self.material[piece.color().index()] += piece_value(piece.kind());
-        self.psq[piece.color().index()] += psq_value(piece, sq) as i32;
+        let (value, psq) = self.piece_score(piece, sq);
+        self.material[piece.color().index()] += value;
+        self.psq[piece.color().index()] += psq;
     }
 
     pub(crate) fn remove(&mut self, sq: Square) -> Piece {
@@ -142,11 +214,19 @@ impl Position {
         self.key ^= z.key_piece[piece.index()][sq.raw() as usize];
         self.lock ^= z.lock_piece[piece.index()][sq.raw() as usize];
 
-        self.material[piece.color().index()] -= piece_value(piece.kind());
-        self.psq[piece.color().index()] -= psq_value(piece, sq) as i32;
+        let (value, psq) = self.piece_score(piece, sq);
+        self.material[piece.color().index()] -= value;
+        self.psq[piece.color().index()] -= psq;
         piece
     }
 
+    /// Material and PST contribution of `piece` on `sq`.
+    #[inline]
+    fn piece_score(&self, piece: Piece, sq: Square) -> (i32, i32) {
+        let params = self.eval_params();
+        (params.piece_value(piece.kind()), params.psq(piece, sq) as i32)
+    }
+
     pub(crate) fn flip_side_to_move(&mut self) {
         self.stm = self.stm.flip();
         let z = &*ZOBRIST;
@@ -164,9 +244,40 @@ impl Position {
     // make_move / undo_move
     // --------------------------------------------------------------------
 
+    /// Play `mv` if it is legal here, returning the token [`try_undo_move`](Self::try_undo_move)
+    /// takes it back with. Leaves the position untouched and returns `None` otherwise.
+    pub fn try_make_move(&mut self, mv: Move) -> Option<UndoInfo> {
+        if self.is_legal(mv) { Some(self.make_move(mv)) } else { None }
+    }
+
+    /// Take back `mv`, the last move played, given the token its
+    /// [`try_make_move`](Self::try_make_move) returned. Returns `false` and leaves the
+    /// position untouched when the pair does not undo to the position it was made from.
+    pub fn try_undo_move(&mut self, mv: Move, info: UndoInfo) -> bool {
+        let (src, dst) = (mv.src(), mv.dst());
+        let Some(mover) = self.piece_at(dst).filter(|p| p.color() != self.stm) else {
+            return false;
+        };
+        if self.piece_at(src).is_some() || src == dst || info.captured.is_some_and(|p| p.color() != self.stm) {
+            return false;
+        }
+        let z = &*ZOBRIST;
+        let mut key = self.key ^ z.key_stm;
+        key ^= z.key_piece[mover.index()][dst.raw() as usize] ^ z.key_piece[mover.index()][src.raw() as usize];
+        if let Some(captured) = info.captured {
+            key ^= z.key_piece[captured.index()][dst.raw() as usize];
+        }
+        if key != info.key_before {
+            return false;
+        }
+        self.undo_move(mv, info);
+        true
+    }
+
     /// Apply `mv` to the board. Returns the undo information; caller must pass it back to
     /// `undo_move`. Caller guarantees the move is *pseudo-legal* — king-safety is checked
-    /// separately via `is_in_check` after the move.
+    /// separately via `is_in_check` after the move. Anything else (an empty source square,
+    /// a capture of one's own piece) corrupts the position.
     pub(crate) fn make_move(&mut self, mv: Move) -> UndoInfo {
         let src = mv.src();
         let dst = mv.dst();
@@ -195,6 +306,7 @@ impl Position {
         debug_assert_eq!(self.lock, info.lock_before);
     }
 
+    /// Take back `mv`, which must be the last move made with the `info` it returned.
     pub(crate) fn undo_move(&mut self, mv: Move, info: UndoInfo) {
         let src = mv.src();
         let dst = mv.dst();
@@ -287,6 +399,184 @@ impl Position {
         attacks.has(king)
     }
 
+    /// Is `mv` a legal move for the side to move? Rejects moves that aren't pseudo-legal
+    /// (wrong side, illegal geometry) and moves that leave the own king attacked.
+    pub fn is_legal(&self, mv: Move) -> bool {
+        let mut pseudo = MoveList::new();
+        generate_pseudo(self, &mut pseudo);
+        pseudo.as_slice().contains(&mv) && self.legal(mv, &self.check_info())
+    }
+
+    /// Static exchange evaluation of `mv`: the material the mover nets (in SEE units, pawn
+    /// = 100) once every profitable recapture on the destination has been played out.
+    /// Positive wins material, negative loses it; a quiet move onto a defended square
+    /// scores the mover's loss.
+    pub fn see(&self, mv: Move) -> i32 { see::see(self, mv) }
+
+    /// Tactical weaknesses of `color`'s position; see [`Threats`].
+    pub fn threats(&self, color: Color) -> Threats {
+        let them = color.flip();
+        let mut hanging = BitBoard::EMPTY;
+        for sq in self.color_occupancy(color) - self.pieces(color, PieceType::King) {
+            let attackers = self.attackers_to(sq, them);
+            if let Some((from, _)) = see::least_valuable(self, attackers, them)
+                && see::see(self, Move::new(from, sq)) > 0
+            {
+                hanging |= BitBoard::from_square(sq);
+            }
+        }
+        let info = self.check_info_for(color);
+        let own = self.color_occupancy(color) - self.pieces(color, PieceType::King);
+        Threats { hanging, pinned: info.pins & own, cannon_pinned: info.screens & own }
+    }
+
+    /// Enemy pieces currently giving check to the side to move.
+    pub fn checkers(&self) -> BitBoard { self.check_info().checkers }
+
+    /// Pieces of `color` attacking `sq` on the current board. A king counts only against
+    /// the opposing king, through the flying-general rule.
+    pub fn attackers_to(&self, sq: Square, color: Color) -> BitBoard {
+        let mut attackers = see::attackers_to(self, sq, self.occ) & self.color_occupancy(color);
+        if let Some(king) = self.king_square(color)
+            && self.king_square(color.flip()) == Some(sq)
+            && king.file() == sq.file()
+            && (between(king, sq) & self.occ).is_empty()
+        {
+            attackers |= BitBoard::from_square(king);
+        }
+        attackers
+    }
+
+    /// Checkers, pin-style blockers and open cannon lanes around the side to move's king.
+    #[inline]
+    pub(crate) fn check_info(&self) -> CheckInfo { self.check_info_for(self.stm) }
+
+    /// [`Position::check_info`] for `us`'s king, whoever is to move.
+    fn check_info_for(&self, us: Color) -> CheckInfo {
+        let them = us.flip();
+        let mut info = CheckInfo::default();
+        let Some(king) = self.king_square(us) else { return info };
+
+        // Rook and flying-general lines: one piece in between pins it, none is check.
+        let line = self.pieces(them, PieceType::Rook) | self.pieces(them, PieceType::King);
+        for sq in line {
+            if sq.rank() != king.rank() && sq.file() != king.file() {
+                continue;
+            }
+            let inside = between(king, sq) & self.occ;
+            match inside.popcount() {
+                0 => info.checkers |= BitBoard::from_square(sq),
+                1 => info.pins |= inside,
+                _ => {}
+            }
+        }
+
+        // Cannon lines: an open lane turns any arriving piece into a screen, one piece is
+        // check, and with two in the way removing either one gives check.
+        for sq in self.pieces(them, PieceType::Cannon) {
+            if sq.rank() != king.rank() && sq.file() != king.file() {
+                continue;
+            }
+            let path = between(king, sq);
+            let inside = path & self.occ;
+            match inside.popcount() {
+                0 => info.cannon_lanes |= path,
+                1 => info.checkers |= BitBoard::from_square(sq),
+                2 => info.screens |= inside,
+                _ => {}
+            }
+        }
+
+        // Knights: the leg next to the knight decides; an occupied leg is a blocker.
+        for sq in self.pieces(them, PieceType::Knight) {
+            for entry in KNIGHT_RAYS[sq.raw() as usize].iter() {
+                if !entry.destinations.has(king) {
+                    continue;
+                }
+                if (entry.blocker & self.occ).is_empty() {
+                    info.checkers |= BitBoard::from_square(sq);
+                } else {
+                    info.pins |= entry.blocker;
+                }
+            }
+        }
+
+        for sq in self.pieces(them, PieceType::Pawn) {
+            if PAWN_ATTACKS[them.index()][sq.raw() as usize].has(king) {
+                info.checkers |= BitBoard::from_square(sq);
+            }
+        }
+        info
+    }
+
+    /// Is the pseudo-legal `mv` legal for the side to move? `info` must come from
+    /// [`Position::check_info`] on this position. Moves that cannot affect the king are
+    /// accepted outright; the rest go through an occupancy-only attack test.
+    pub(crate) fn legal(&self, mv: Move, info: &CheckInfo) -> bool {
+        let src = mv.src();
+        if info.checkers.is_empty()
+            && !(info.pins | info.screens).has(src)
+            && !info.cannon_lanes.has(mv.dst())
+            && self.king_square(self.stm) != Some(src)
+        {
+            return true;
+        }
+        !self.king_attacked_after(mv)
+    }
+
+    /// Would the mover's king be attacked once `mv` is played? Works on the occupancy
+    /// alone: the captured piece (if any) stops attacking and the board is never modified.
+    fn king_attacked_after(&self, mv: Move) -> bool {
+        let us = self.stm;
+        let them = us.flip();
+        let src = mv.src();
+        let dst = mv.dst();
+        let Some(own_king) = self.king_square(us) else { return false };
+        let king = if own_king == src { dst } else { own_king };
+        let occ = (self.occ - BitBoard::from_square(src)) | BitBoard::from_square(dst);
+        let enemy = |kind: PieceType| self.pieces(them, kind) - BitBoard::from_square(dst);
+
+        // Enemy kings only ever share a file with ours, so the rook ray covers flying general.
+        if (rook_attacks(king, occ) & (enemy(PieceType::Rook) | enemy(PieceType::King))).any() {
+            return true;
+        }
+        if (cannon_attacks(king, occ).1 & enemy(PieceType::Cannon)).any() {
+            return true;
+        }
+        if enemy(PieceType::Knight).into_iter().any(|sq| knight_attacks(sq, occ).has(king)) {
+            return true;
+        }
+        enemy(PieceType::Pawn).into_iter().any(|sq| PAWN_ATTACKS[them.index()][sq.raw() as usize].has(king))
+    }
+
+    /// Does the pseudo-legal `mv` put the opponent in check? Covers direct checks, checks
+    /// discovered by vacating a rook line or knight leg, a cannon gaining or losing a
+    /// screen, and the flying-general rule — all from occupancy, without making the move.
+    pub fn gives_check(&self, mv: Move) -> bool {
+        let us = self.stm;
+        let them = us.flip();
+        let Some(king) = self.king_square(them) else { return false };
+        let src = mv.src();
+        let dst = mv.dst();
+        let Some(mover) = self.piece_at(src) else { return false };
+        let occ = (self.occ - BitBoard::from_square(src)) | BitBoard::from_square(dst);
+        let ours = |kind: PieceType| {
+            let bb = self.pieces(us, kind);
+            if kind == mover.kind() { (bb - BitBoard::from_square(src)) | BitBoard::from_square(dst) } else { bb }
+        };
+
+        if (rook_attacks(king, occ) & (ours(PieceType::Rook) | ours(PieceType::King))).any() {
+            return true;
+        }
+        if (cannon_attacks(king, occ).1 & ours(PieceType::Cannon)).any() {
+            return true;
+        }
+        if ours(PieceType::Knight).into_iter().any(|sq| knight_attacks(sq, occ).has(king)) {
+            return true;
+        }
+        ours(PieceType::Pawn).into_iter().any(|sq| PAWN_ATTACKS[us.index()][sq.raw() as usize].has(king))
+    }
+
     // --------------------------------------------------------------------
     // Incremental evaluation helpers used by search/eval.
     // --------------------------------------------------------------------
@@ -299,23 +589,6 @@ impl Position {
     pub(crate) fn psq_score(&self) -> i32 { self.psq[0] - self.psq[1] }
 }
 
-// --------------------------------------------------------------------
-// Piece values (traditional Chinese-chess point count; adjust in eval.rs later).
-// --------------------------------------------------------------------
-
-#[inline]
-const fn piece_value(kind: PieceType) -> i32 {
-    match kind {
-        PieceType::King => 10_000,
-        PieceType::Advisor => 20,
-        PieceType::Bishop => 20,
-        PieceType::Knight => 40,
-        PieceType::Rook => 90,
-        PieceType::Cannon => 45,
-        PieceType::Pawn => 10,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,6 +607,56 @@ mod tests {
         assert_eq!(p.zobrist_lock(), 0);
     }
 
+    #[test]
+    fn debug_skips_eval_params() {
+        let mut p = Position::from_fen(crate::fen::STARTING_FEN).unwrap();
+        p.set_eval_params(Arc::new(EvalParams::default()));
+        let debug = format!("{p:?}");
+        assert!(debug.starts_with("Position {") && debug.contains("stm: Red"), "{debug}");
+        assert!(!debug.contains("pst"), "{debug}");
+    }
+
+    #[test]
+    fn public_queries_agree_with_make_undo() {
+        // Red rook on e4 pinned to its king by the black rook on e9; black cannon on a2
+        // looking along rank 2 over the red pawn on c2.
+        let mut p = Position::from_fen("3kr4/9/9/9/9/4R4/9/c1P6/9/4K4 w").unwrap();
+        let sideways = Move::from_iccs("e4-d4").unwrap();
+        let forward = Move::from_iccs("e4-e8").unwrap();
+        assert!(!p.is_legal(sideways));
+        assert!(p.is_legal(forward));
+        assert!(!p.is_legal(Move::from_iccs("e9-e8").unwrap()), "black piece, red to move");
+
+        let check = Move::from_iccs("e4-e8").unwrap();
+        assert!(!p.gives_check(check));
+        let fen = p.to_fen();
+        assert!(p.try_make_move(sideways).is_none());
+        let undo = p.try_make_move(check).unwrap();
+        assert!(!p.is_in_check(Color::Black));
+        assert!(!p.try_undo_move(Move::from_iccs("e9-e8").unwrap(), undo));
+        let stale = UndoInfo { key_before: undo.key_before ^ 1, ..undo };
+        assert!(!p.try_undo_move(check, stale));
+        assert!(p.try_undo_move(check, undo));
+        assert_eq!(p.to_fen(), fen);
+
+        let e4 = Square::from_iccs("e4").unwrap();
+        let e2 = Square::from_iccs("e2").unwrap();
+        assert_eq!(p.attackers_to(e4, Color::Black), BitBoard::from_square(Square::from_iccs("e9").unwrap()));
+        assert_eq!(p.attackers_to(e2, Color::Black), BitBoard::from_square(Square::from_iccs("a2").unwrap()));
+        assert!(p.attackers_to(e2, Color::Red).has(e4));
+        assert!(p.checkers().is_empty());
+    }
+
+    #[test]
+    fn kings_attack_each_other_only_when_facing() {
+        let p = Position::from_fen("4k4/9/9/9/9/9/9/9/9/4K4 w").unwrap();
+        let black_king = p.king_square(Color::Black).unwrap();
+        let red_king = p.king_square(Color::Red).unwrap();
+        assert_eq!(p.attackers_to(black_king, Color::Red), BitBoard::from_square(red_king));
+        assert_eq!(p.checkers(), BitBoard::from_square(black_king));
+        assert!(p.attackers_to(Square::from_iccs("e5").unwrap(), Color::Red).is_empty());
+    }
+
     #[test]
     fn make_undo_restores_state() {
         let mut p = Position::empty();
@@ -0,0 +1,182 @@
+//! Text diagrams of a [`Position`].
+//!
+//! ```text
+//! 9 r  n  b  a  k  a  b  n  r
+//! 8 .  .  .  .  .  .  .  .  .
+//! ...
+//! 0 R  N  B  A  K  A  B  N  R
+//!   a  b  c  d  e  f  g  h  i
+//! ```
+
+use std::fmt;
+
+use crate::color::Color;
+use crate::mv::Move;
+use crate::piece::Piece;
+use crate::piece::PieceType;
+use crate::position::Position;
+use crate::square::Square;
+
+/// How pieces are drawn.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Glyphs {
+    /// FEN letters, uppercase for red.
+    #[default]
+    Ascii,
+    /// Traditional characters: 帥仕相傌俥炮兵 for red, 將士象馬車砲卒 for black.
+    Chinese,
+}
+
+/// Options of [`Position::render`].
+#[derive(Copy, Clone, Debug)]
+pub struct RenderStyle {
+    glyphs: Glyphs,
+    coordinates: bool,
+    last_move: Option<Move>,
+    orientation: Color,
+}
+
+impl Default for RenderStyle {
+    fn default() -> Self { Self::new() }
+}
+
+impl RenderStyle {
+    /// ASCII letters with coordinates, seen from red's side.
+    pub fn new() -> Self {
+        RenderStyle { glyphs: Glyphs::Ascii, coordinates: true, last_move: None, orientation: Color::Red }
+    }
+
+    #[must_use]
+    pub fn glyphs(mut self, glyphs: Glyphs) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// Rank digits down the left and ICCS file letters along the bottom.
+    #[must_use]
+    pub fn coordinates(mut self, yes: bool) -> Self {
+        self.coordinates = yes;
+        self
+    }
+
+    /// Bracket the source and destination squares of `mv`.
+    #[must_use]
+    pub fn last_move(mut self, mv: Option<Move>) -> Self {
+        self.last_move = mv;
+        self
+    }
+
+    /// The side sitting at the bottom of the diagram.
+    #[must_use]
+    pub fn orientation(mut self, color: Color) -> Self {
+        self.orientation = color;
+        self
+    }
+}
+
+pub(crate) fn chinese_glyph(piece: Piece) -> char {
+    match (piece.color(), piece.kind()) {
+        (Color::Red, PieceType::King) => '帥',
+        (Color::Red, PieceType::Advisor) => '仕',
+        (Color::Red, PieceType::Bishop) => '相',
+        (Color::Red, PieceType::Knight) => '傌',
+        (Color::Red, PieceType::Rook) => '俥',
+        (Color::Red, PieceType::Cannon) => '炮',
+        (Color::Red, PieceType::Pawn) => '兵',
+        (Color::Black, PieceType::King) => '將',
+        (Color::Black, PieceType::Advisor) => '士',
+        (Color::Black, PieceType::Bishop) => '象',
+        (Color::Black, PieceType::Knight) => '馬',
+        (Color::Black, PieceType::Rook) => '車',
+        (Color::Black, PieceType::Cannon) => '砲',
+        (Color::Black, PieceType::Pawn) => '卒',
+    }
+}
+
+impl Position {
+    /// A text diagram, one line per rank. Every cell is a glyph between two spaces, or
+    /// between brackets when it is part of the style's last move; Chinese glyphs and the
+    /// full-width `＋` of empty points are two columns wide, so use a monospace font
+    /// that draws CJK at double width.
+    pub fn render(&self, style: RenderStyle) -> String {
+        let flipped = style.orientation == Color::Black;
+        let ranks: Vec<u8> = if flipped { (0..10).collect() } else { (0..10).rev().collect() };
+        let files: Vec<u8> = if flipped { (0..9).rev().collect() } else { (0..9).collect() };
+        let highlighted = |sq: Square| style.last_move.is_some_and(|mv| mv.src() == sq || mv.dst() == sq);
+
+        let mut out = String::new();
+        for &rank in &ranks {
+            if style.coordinates {
+                out.push((b'0' + rank) as char);
+            }
+            for &file in &files {
+                let sq = Square::from_rank_file(rank, file).expect("rank and file on the board");
+                let glyph = match (self.piece_at(sq), style.glyphs) {
+                    (Some(piece), Glyphs::Ascii) => piece.fen_char(),
+                    (Some(piece), Glyphs::Chinese) => chinese_glyph(piece),
+                    (None, Glyphs::Ascii) => '.',
+                    (None, Glyphs::Chinese) => '＋',
+                };
+                let (open, close) = if highlighted(sq) { ('[', ']') } else { (' ', ' ') };
+                out.push(open);
+                out.push(glyph);
+                out.push(close);
+            }
+            out.truncate(out.trim_end().len());
+            out.push('\n');
+        }
+        if style.coordinates {
+            let width = if style.glyphs == Glyphs::Chinese { 4 } else { 3 };
+            let mut footer = String::new();
+            for &file in &files {
+                footer.push_str(&format!("{:>w$}", (b'a' + file) as char, w = width - 1));
+                footer.push(' ');
+            }
+            out.push(' ');
+            out.push_str(footer.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// [`Position::render`] with the default style; the alternate form (`{:#}`) uses
+/// Chinese glyphs.
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let glyphs = if f.alternate() { Glyphs::Chinese } else { Glyphs::Ascii };
+        f.write_str(&self.render(RenderStyle::new().glyphs(glyphs)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::STARTING_FEN;
+
+    #[test]
+    fn renders_styles_and_orientation() {
+        let pos = Position::from_fen(STARTING_FEN).unwrap();
+        let ascii = pos.to_string();
+        let lines: Vec<&str> = ascii.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "9 r  n  b  a  k  a  b  n  r");
+        assert_eq!(lines[7], "2 .  C  .  .  .  .  .  C  .");
+        assert_eq!(lines[10], "  a  b  c  d  e  f  g  h  i");
+
+        let mv = Move::from_iccs("h2e2").unwrap();
+        let mut after = pos.clone();
+        after.make_move(mv);
+        let marked = after.render(RenderStyle::new().coordinates(false).last_move(Some(mv)));
+        assert_eq!(marked.lines().nth(7), Some(" .  C  .  . [C] .  . [.] ."));
+
+        let black = pos.render(RenderStyle::new().orientation(Color::Black));
+        assert!(black.starts_with("0 R  N  B  A  K  A  B  N  R\n"));
+        assert!(black.ends_with("  i  h  g  f  e  d  c  b  a\n"));
+
+        let chinese = format!("{pos:#}");
+        assert!(chinese.starts_with("9 車  馬  象  士  將  士  象  馬  車\n"));
+        assert!(chinese.contains("0 俥  傌  相  仕  帥  仕  相  傌  俥\n"));
+        assert!(chinese.ends_with("   a   b   c   d   e   f   g   h   i\n"));
+    }
+}
@@ -0,0 +1,258 @@
+//! Game review: replay a finished game, score every played move against the engine's
+//! choice and grade the difference.
+//!
+//! Losses are in the evaluation's own units, which are not centipawns: the piece-square
+//! tables put a pawn across the river at about 20-30, a knight or cannon at about 90-100
+//! and a rook at about 200.
+
+use std::fmt;
+
+use crate::color::Color;
+use crate::engine::Engine;
+use crate::error::ChessAIError;
+use crate::limits::Limits;
+use crate::mv::Move;
+
+/// Loss from which a move counts as an inaccuracy: about half an advanced pawn.
+pub const INACCURACY_LOSS: i32 = 15;
+/// Loss from which a move counts as a mistake: about an advanced pawn.
+pub const MISTAKE_LOSS: i32 = 40;
+/// Loss from which a move counts as a blunder: a little less than a knight or cannon, so
+/// hanging one is always a blunder.
+pub const BLUNDER_LOSS: i32 = 80;
+
+/// Scores are clamped to this before taking the loss, so walking out of a mate costs the
+/// same as dropping a decisive amount of material (over two rooks) rather than thousands.
+const LOSS_CLAMP: i32 = 500;
+
+/// Grade of a played move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MoveJudgement {
+    /// The engine's own choice, or scored at least as well.
+    Best,
+    /// Lost less than [`INACCURACY_LOSS`].
+    Good,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl MoveJudgement {
+    fn from_loss(loss: i32) -> MoveJudgement {
+        match loss {
+            l if l <= 0 => MoveJudgement::Best,
+            l if l < INACCURACY_LOSS => MoveJudgement::Good,
+            l if l < MISTAKE_LOSS => MoveJudgement::Inaccuracy,
+            l if l < BLUNDER_LOSS => MoveJudgement::Mistake,
+            _ => MoveJudgement::Blunder,
+        }
+    }
+
+    /// Conventional annotation glyph: `?!`, `?` or `??`; empty for good moves.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            MoveJudgement::Best | MoveJudgement::Good => "",
+            MoveJudgement::Inaccuracy => "?!",
+            MoveJudgement::Mistake => "?",
+            MoveJudgement::Blunder => "??",
+        }
+    }
+}
+
+/// One reviewed ply. Scores are from the mover's point of view.
+#[derive(Clone, Debug)]
+pub struct AnalysedMove {
+    /// 1-based ply number within the reviewed game.
+    pub ply: usize,
+    pub side: Color,
+    pub played: Move,
+    /// Score of `played`, from a search restricted to it.
+    pub score: i32,
+    /// The engine's choice, or `played` when the search restricted to it scored higher.
+    pub best_move: Move,
+    /// Score of `best_line` as searched.
+    pub best_score: i32,
+    /// Principal variation starting with `best_move`.
+    pub best_line: Vec<Move>,
+    /// Evaluation units lost against `best_move`, never negative.
+    pub loss: i32,
+    pub judgement: MoveJudgement,
+}
+
+impl fmt::Display for AnalysedMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>3}. {}{} {:+}", self.ply, self.played, self.judgement.symbol(), self.score)?;
+        if self.played != self.best_move {
+            write!(f, " (best {} {:+}, loss {})", self.best_move, self.best_score, self.loss)?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of [`Engine::analyse_game`].
+#[derive(Clone, Debug, Default)]
+pub struct GameAnalysis {
+    /// FEN the game was replayed from.
+    pub fen: String,
+    pub moves: Vec<AnalysedMove>,
+}
+
+impl GameAnalysis {
+    /// How many of `side`'s moves received `judgement`.
+    pub fn count(&self, side: Color, judgement: MoveJudgement) -> usize {
+        self.moves.iter().filter(|m| m.side == side && m.judgement == judgement).count()
+    }
+
+    /// Mean loss of `side`'s moves.
+    pub fn average_loss(&self, side: Color) -> f64 {
+        let losses: Vec<i32> = self.moves.iter().filter(|m| m.side == side).map(|m| m.loss).collect();
+        if losses.is_empty() { 0.0 } else { losses.iter().sum::<i32>() as f64 / losses.len() as f64 }
+    }
+}
+
+impl fmt::Display for GameAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for analysed in &self.moves {
+            writeln!(f, "{analysed}")?;
+        }
+        for side in [Color::Red, Color::Black] {
+            writeln!(
+                f,
+                "{side}: {} inaccuracies, {} mistakes, {} blunders, average loss {:.0}",
+                self.count(side, MoveJudgement::Inaccuracy),
+                self.count(side, MoveJudgement::Mistake),
+                self.count(side, MoveJudgement::Blunder),
+                self.average_loss(side)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Engine {
+    /// Review `moves` played from the current position. Each position is searched with
+    /// `limits`, single-threaded and without the book; a played move other than the best
+    /// one is scored by a second search restricted to it, and graded by the score
+    /// difference. The engine is left on the position it started from, with its hash
+    /// table warmed by the review.
+    pub fn analyse_game(&mut self, moves: &[Move], limits: Limits) -> Result<GameAnalysis, ChessAIError> {
+        let mut review = GameAnalysis { fen: self.fen(), moves: Vec::with_capacity(moves.len()) };
+        let mut result = Ok(());
+        for (idx, &played) in moves.iter().enumerate() {
+            if !self.position().is_legal(played) {
+                result = Err(ChessAIError::IllegalGameMove { ply: idx + 1, mv: played });
+                break;
+            }
+            let side = self.side_to_move();
+            let best = self.search_root_moves(limits, &[]);
+            let engine_move = best.best_move.unwrap_or(played);
+            let (score, played_line) = match engine_move == played {
+                true => (best.score, None),
+                false => {
+                    let restricted = self.search_root_moves(limits, &[played]);
+                    (restricted.score, Some(restricted.pv))
+                }
+            };
+            // A search restricted to the played move can see further than the full one did;
+            // the played move is then the best one known, with its own line.
+            let (best_move, best_score, best_line) = match played_line {
+                Some(line) if score > best.score => (played, score, line),
+                _ => (engine_move, best.score, best.pv),
+            };
+            let loss = (best_score.clamp(-LOSS_CLAMP, LOSS_CLAMP) - score.clamp(-LOSS_CLAMP, LOSS_CLAMP)).max(0);
+            let judgement = if best_move == played { MoveJudgement::Best } else { MoveJudgement::from_loss(loss) };
+            review.moves.push(AnalysedMove {
+                ply: idx + 1,
+                side,
+                played,
+                score,
+                best_move,
+                best_score,
+                best_line,
+                loss,
+                judgement,
+            });
+            self.make_move(played);
+        }
+        for _ in 0..review.moves.len() {
+            self.undo_move();
+        }
+        result.map(|()| review)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grades_a_hanging_rook_as_a_blunder() {
+        let mut engine = Engine::builder().use_book(false).hash_size(1).build();
+        engine.set_fen("3k5/9/9/9/9/9/9/9/4r4/R3K4 w").unwrap();
+        let fen = engine.fen();
+        // Red's king can take the checking rook for free but steps aside instead.
+        let moves: Vec<Move> = ["e0f0", "e1e8"].iter().map(|m| Move::from_iccs(m).unwrap()).collect();
+        let review = engine.analyse_game(&moves, Limits::new().depth(4)).unwrap();
+        assert_eq!(engine.fen(), fen);
+        assert_eq!(review.fen, fen);
+        assert_eq!(review.moves.len(), 2);
+
+        let first = &review.moves[0];
+        assert_eq!((first.ply, first.side), (1, Color::Red));
+        assert_eq!(first.best_move, Move::from_iccs("e0e1").unwrap());
+        assert_eq!(first.best_line.first(), Some(&first.best_move));
+        assert_eq!(first.judgement, MoveJudgement::Blunder);
+        assert!(first.best_score >= first.score);
+        assert!(first.loss >= BLUNDER_LOSS && first.to_string().starts_with("  1. e0-f0?? "));
+        assert_eq!(review.moves[1].side, Color::Black);
+
+        let illegal = [Move::from_iccs("e0e2").unwrap()];
+        assert_eq!(
+            engine.analyse_game(&illegal, Limits::new().depth(2)).unwrap_err(),
+            ChessAIError::IllegalGameMove { ply: 1, mv: illegal[0] }
+        );
+        assert_eq!(engine.fen(), fen);
+    }
+
+    #[test]
+    fn grades_a_hanging_knight_as_a_blunder() {
+        let mut engine = Engine::builder().use_book(false).hash_size(1).build();
+        engine.set_fen("3k5/9/9/9/4p4/9/2N6/9/9/4K4 w").unwrap();
+        // The knight jumps to e4, where the pawn takes it.
+        let review = engine.analyse_game(&[Move::from_iccs("c3e4").unwrap()], Limits::new().depth(4)).unwrap();
+        let played = &review.moves[0];
+        assert_eq!(played.judgement, MoveJudgement::Blunder, "{played}");
+    }
+
+    #[test]
+    fn best_fields_describe_one_searched_line() {
+        let mut engine = Engine::builder().use_book(false).hash_size(1).build();
+        let moves: Vec<Move> = ["h2e2", "h9g7", "h0g2", "i9h9", "i0h0", "b9c7", "c3c4", "h7i7"]
+            .iter()
+            .map(|m| Move::from_iccs(m).unwrap())
+            .collect();
+        let review = engine.analyse_game(&moves, Limits::new().nodes(20000)).unwrap();
+        // At this budget the search restricted to c3c4 outscores the full search, which
+        // preferred another move: c3c4 becomes the best move, with its own line and score.
+        let pawn = &review.moves[6];
+        assert_eq!((pawn.best_move, pawn.judgement), (pawn.played, MoveJudgement::Best), "{pawn}");
+        for m in &review.moves {
+            assert_eq!(m.best_line.first(), Some(&m.best_move), "{m}");
+            assert_eq!(m.judgement == MoveJudgement::Best, m.best_move == m.played || m.loss == 0, "{m}");
+            match m.best_move == m.played {
+                true => assert_eq!((m.best_score, m.loss), (m.score, 0), "{m}"),
+                false => assert_eq!(m.loss, (m.best_score - m.score).max(0), "{m}"),
+            }
+        }
+    }
+
+    #[test]
+    fn thresholds_order_judgements() {
+        assert_eq!(MoveJudgement::from_loss(0), MoveJudgement::Best);
+        assert_eq!(MoveJudgement::from_loss(INACCURACY_LOSS - 1), MoveJudgement::Good);
+        assert_eq!(MoveJudgement::from_loss(INACCURACY_LOSS), MoveJudgement::Inaccuracy);
+        assert_eq!(MoveJudgement::from_loss(MISTAKE_LOSS), MoveJudgement::Mistake);
+        assert_eq!(MoveJudgement::from_loss(BLUNDER_LOSS), MoveJudgement::Blunder);
+        assert!(MoveJudgement::Blunder > MoveJudgement::Mistake);
+    }
+}
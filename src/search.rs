@@ -1,26 +1,36 @@
+use std::fmt;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::LazyLock;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 use std::time::Instant;
 
+use crate::error::ChessAIError;
 use crate::eval::BAN_VALUE;
 use crate::eval::MATE_VALUE;
-use crate::eval::NULL_OKAY_MARGIN;
 use crate::eval::WIN_VALUE;
 use crate::eval::draw_value;
 use crate::eval::evaluate;
+use crate::eval::param_tokens;
 use crate::limits::Limits;
 use crate::limits::MAX_SEARCH_DEPTH;
+use crate::mate::MateOutcome;
 use crate::movegen::MoveList;
 use crate::movegen::generate_captures;
-use crate::movegen::generate_pseudo;
+use crate::movegen::generate_evasions;
+use crate::movegen::generate_legal;
+use crate::movegen::generate_quiet_checks;
 use crate::mv::Move;
 use crate::picker::MovePicker;
 use crate::piece::Piece;
 use crate::piece::PieceType;
 use crate::position::Position;
 use crate::see::see;
+use crate::tablebase::Tablebases;
+use crate::tablebase::TbProbe;
+use crate::tablebase::Wdl;
 use crate::tt::Bound;
 use crate::tt::TranspositionTable;
 use crate::tt::mate_score_from_tt;
@@ -31,7 +41,12 @@ pub(crate) type ThreadId = u8;
 pub(crate) const MAX_PLY: usize = 64;
 
 const INF: i32 = 32_000;
-const ASPIRATION_DELTA: i32 = 16;
+
+/// Quiescence plies (counted at nodes not in check) that also try quiet checking moves.
+pub(crate) const DEFAULT_QSEARCH_CHECKS: u8 = 1;
+
+/// Natural move limit: 60 moves by each side (120 plies) without a capture is a draw.
+pub(crate) const MOVE_LIMIT_PLIES: u16 = 120;
 
 /// Lazy SMP depth-skip pattern. Helper threads (id ≥ 1) deliberately skip selected
 /// iterative-deepening depths so that workers spread across the depth axis instead of
@@ -40,18 +55,241 @@ const SKIP_SIZE: [u8; 20] = [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4,
 const SKIP_PHASE: [u8; 20] = [0, 1, 0, 1, 2, 3, 0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5, 6, 7];
 
 #[inline]
-fn should_skip_depth(thread_id: ThreadId, depth: u8) -> bool {
+fn should_skip_depth(params: &SearchParams, thread_id: ThreadId, depth: u8) -> bool {
     if thread_id == 0 {
         return false;
     }
-    let i = ((thread_id - 1) as usize) % SKIP_SIZE.len();
-    let phase = SKIP_PHASE[i];
-    let size = SKIP_SIZE[i];
+    let i = ((thread_id - 1) as usize) % params.skip_size.len();
+    let phase = params.skip_phase[i];
+    let size = params.skip_size[i];
     ((depth + phase) / size) % 2 == 1
 }
 
+// --------------------------------------------------------------------
+// Runtime-tunable search parameters
+// --------------------------------------------------------------------
+
+/// Name, lower bound and upper bound of every scalar in [`SearchParams`], in the order of
+/// [`SearchParams::scalars`]. The bounds keep divisors positive and margins sane; the SPSA
+/// tuner stays inside them.
+pub(crate) const SCALAR_PARAMS: [(&str, i32, i32); 18] = [
+    ("aspiration_delta", 4, 100),
+    ("lmr_base", -100, 200),
+    ("lmr_divisor", 100, 500),
+    ("null_move_base", 1, 5),
+    ("null_move_divisor", 2, 8),
+    ("rfp_margin", 40, 400),
+    ("rfp_margin_improving", 40, 400),
+    ("razor_base", 0, 600),
+    ("razor_margin", 0, 300),
+    ("futility_base", 0, 300),
+    ("futility_margin", 20, 300),
+    ("futility_base_improving", 0, 300),
+    ("futility_margin_improving", 20, 300),
+    ("lmp_base", 1, 20),
+    ("qsearch_delta", 0, 500),
+    ("singular_min_depth", 4, 16),
+    ("singular_tt_depth", 1, 6),
+    ("singular_margin", 1, 8),
+];
+
+/// Search heuristics' constants an `Engine` is built with. `Default` reproduces the
+/// hand-set values, so experiments only need to override what they change.
+///
+/// The text form (see `Display` / `FromStr`) is one `key value…` line per parameter, `#`
+/// starting a comment, like [`EvalParams`](crate::EvalParams):
+///
+/// ```text
+/// aspiration_delta 16
+/// lmr_base 60
+/// skip_size 1 1 2 2 2 2 3 3 3 3 3 3 4 4 4 4 4 4 4 4
+/// ```
+///
+/// Keys may appear in any order and any subset; omitted keys keep their default. Values
+/// outside the range a parameter supports are rejected when parsing, and clamped into it
+/// by [`EngineBuilder::search_params`](crate::EngineBuilder::search_params).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchParams {
+    /// Half-width of the first aspiration window around the previous iteration's score.
+    pub aspiration_delta: i32,
+    /// Late-move reduction `base + ln(depth) · ln(move) / divisor`, both in hundredths of a ply.
+    pub lmr_base: i32,
+    pub lmr_divisor: i32,
+    /// Null-move reduction `base + depth / divisor`.
+    pub null_move_base: i32,
+    pub null_move_divisor: i32,
+    /// Reverse futility margin per ply of depth, when not improving and when improving.
+    pub rfp_margin: i32,
+    pub rfp_margin_improving: i32,
+    /// Razoring margin `base + margin · depth`.
+    pub razor_base: i32,
+    pub razor_margin: i32,
+    /// Quiet-move futility margin `base + margin · depth`, when not improving and when improving.
+    pub futility_base: i32,
+    pub futility_margin: i32,
+    pub futility_base_improving: i32,
+    pub futility_margin_improving: i32,
+    /// Late-move pruning keeps `lmp_base + depth²` quiet moves (half that when not improving).
+    pub lmp_base: i32,
+    /// Quiescence delta pruning: skip captures that stay below alpha by more than this.
+    pub qsearch_delta: i32,
+    /// Singular extensions: minimum depth, how much shallower the TT entry may be, and the
+    /// per-ply margin below the TT score that other moves must stay under.
+    pub singular_min_depth: i32,
+    pub singular_tt_depth: i32,
+    pub singular_margin: i32,
+    /// Lazy SMP depth-skip pattern by helper thread: cycle length and phase.
+    pub skip_size: [u8; 20],
+    pub skip_phase: [u8; 20],
+}
+
+impl Default for SearchParams {
+    fn default() -> Self {
+        SearchParams {
+            aspiration_delta: 16,
+            lmr_base: 60,
+            lmr_divisor: 235,
+            null_move_base: 2,
+            null_move_divisor: 4,
+            rfp_margin: 160,
+            rfp_margin_improving: 120,
+            razor_base: 200,
+            razor_margin: 80,
+            futility_base: 50,
+            futility_margin: 70,
+            futility_base_improving: 75,
+            futility_margin_improving: 100,
+            lmp_base: 5,
+            qsearch_delta: 150,
+            singular_min_depth: 8,
+            singular_tt_depth: 3,
+            singular_margin: 2,
+            skip_size: SKIP_SIZE,
+            skip_phase: SKIP_PHASE,
+        }
+    }
+}
+
+/// Shared default instance for searches run without an engine.
+pub(crate) static DEFAULT_SEARCH_PARAMS: LazyLock<Arc<SearchParams>> =
+    LazyLock::new(|| Arc::new(SearchParams::default()));
+
+impl SearchParams {
+    /// The scalar parameters, in [`SCALAR_PARAMS`] order.
+    pub(crate) fn scalars(&self) -> [i32; SCALAR_PARAMS.len()] {
+        [
+            self.aspiration_delta,
+            self.lmr_base,
+            self.lmr_divisor,
+            self.null_move_base,
+            self.null_move_divisor,
+            self.rfp_margin,
+            self.rfp_margin_improving,
+            self.razor_base,
+            self.razor_margin,
+            self.futility_base,
+            self.futility_margin,
+            self.futility_base_improving,
+            self.futility_margin_improving,
+            self.lmp_base,
+            self.qsearch_delta,
+            self.singular_min_depth,
+            self.singular_tt_depth,
+            self.singular_margin,
+        ]
+    }
+
+    /// Every parameter pulled into the range it supports (see [`SCALAR_PARAMS`]; skip
+    /// cycle lengths are at least 1), so that no field can make the search divide by zero.
+    pub(crate) fn clamped(mut self) -> Self {
+        for ((_, min, max), value) in SCALAR_PARAMS.iter().zip(self.scalars_mut()) {
+            *value = (*value).clamp(*min, *max);
+        }
+        for size in self.skip_size.iter_mut() {
+            *size = (*size).max(1);
+        }
+        self
+    }
+
+    pub(crate) fn scalars_mut(&mut self) -> [&mut i32; SCALAR_PARAMS.len()] {
+        [
+            &mut self.aspiration_delta,
+            &mut self.lmr_base,
+            &mut self.lmr_divisor,
+            &mut self.null_move_base,
+            &mut self.null_move_divisor,
+            &mut self.rfp_margin,
+            &mut self.rfp_margin_improving,
+            &mut self.razor_base,
+            &mut self.razor_margin,
+            &mut self.futility_base,
+            &mut self.futility_margin,
+            &mut self.futility_base_improving,
+            &mut self.futility_margin_improving,
+            &mut self.lmp_base,
+            &mut self.qsearch_delta,
+            &mut self.singular_min_depth,
+            &mut self.singular_tt_depth,
+            &mut self.singular_margin,
+        ]
+    }
+}
+
+impl fmt::Display for SearchParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# chessai search parameters")?;
+        for ((name, _, _), value) in SCALAR_PARAMS.iter().zip(self.scalars()) {
+            writeln!(f, "{name} {value}")?;
+        }
+        for (name, pattern) in [("skip_size", &self.skip_size), ("skip_phase", &self.skip_phase)] {
+            write!(f, "{name}")?;
+            for v in pattern {
+                write!(f, " {v}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SearchParams {
+    type Err = ChessAIError;
+
+    fn from_str(text: &str) -> Result<SearchParams, ChessAIError> {
+        let mut tokens = param_tokens(text);
+        let err = |line: usize, reason: String| ChessAIError::BadSearchParams { line, reason };
+
+        let mut params = SearchParams::default();
+        while let Some((line, key)) = tokens.next() {
+            let mut number = |min: i32, max: i32| -> Result<i32, ChessAIError> {
+                let (l, tok) = tokens.next().ok_or_else(|| err(line, format!("missing value for {key}")))?;
+                let value = tok.parse::<i32>().map_err(|_| err(l, format!("{tok:?} is not an integer")))?;
+                if !(min..=max).contains(&value) {
+                    return Err(err(l, format!("{key} must be in {min}..={max}, got {value}")));
+                }
+                Ok(value)
+            };
+            if let Some(idx) = SCALAR_PARAMS.iter().position(|(name, _, _)| *name == key) {
+                let (_, min, max) = SCALAR_PARAMS[idx];
+                *params.scalars_mut()[idx] = number(min, max)?;
+                continue;
+            }
+            let (pattern, min) = match key {
+                "skip_size" => (&mut params.skip_size, 1),
+                "skip_phase" => (&mut params.skip_phase, 0),
+                other => return Err(err(line, format!("unknown key {other:?}"))),
+            };
+            for slot in pattern.iter_mut() {
+                *slot = number(min, u8::MAX as i32)? as u8;
+            }
+        }
+        Ok(params)
+    }
+}
+
 /// Snapshot of the best line found so far. Returned by `Search::run` and emitted to
-/// callers via the `SearchInfo` callback on every completed iteration.
+/// callers via the `SearchInfo` callback on every completed iteration, and on every
+/// aspiration fail-high or fail-low with [`bound`](SearchInfo::bound) set accordingly.
 #[derive(Clone, Debug, Default)]
 pub struct SearchInfo {
     pub depth: u8,
@@ -61,6 +299,54 @@ pub struct SearchInfo {
     pub nodes: u64,
     pub time: Duration,
     pub nps: u64,
+    /// Every root line searched this iteration, best first. One entry per
+    /// `Limits::multipv` (fewer if the root has fewer legal moves); empty for book moves.
+    pub lines: Vec<RootLine>,
+    /// Transposition-table occupancy in per mille (entries from this search only).
+    pub hashfull: u16,
+    /// Outcome of a [`Limits::mate`] search; `None` for normal searches.
+    pub mate: Option<MateOutcome>,
+    /// Deepest ply reached this iteration, quiescence included.
+    pub seldepth: u8,
+    /// `score` as a mate distance in moves: positive when the side to move mates,
+    /// negative when it gets mated; `None` for non-mate scores.
+    pub mate_in: Option<i32>,
+    /// Whether `score` is exact or only a bound from an aspiration failure.
+    pub bound: ScoreBound,
+    /// Root move being searched when this snapshot was taken.
+    pub currmove: Option<Move>,
+    /// 1-based index of `currmove` among the root moves searched this iteration.
+    pub currmove_number: u32,
+}
+
+/// Bound type of a reported score.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ScoreBound {
+    /// The score of a completed iteration.
+    #[default]
+    Exact,
+    /// Fail-high: the true score is at least this.
+    Lower,
+    /// Fail-low: the true score is at most this.
+    Upper,
+}
+
+/// Convert a mate score into a mate distance in moves (see [`SearchInfo::mate_in`]).
+pub(crate) fn mate_in(score: i32) -> Option<i32> {
+    if score > BAN_VALUE {
+        Some((MATE_VALUE - score + 1) / 2)
+    } else if score < -BAN_VALUE {
+        Some(-(MATE_VALUE + score) / 2)
+    } else {
+        None
+    }
+}
+
+/// One MultiPV root line: its score (side-to-move perspective) and principal variation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RootLine {
+    pub score: i32,
+    pub pv: Vec<Move>,
 }
 
 pub(crate) struct History {
@@ -137,6 +423,17 @@ pub(crate) struct Search<'a> {
     stop: Arc<AtomicBool>,
 
     pub(crate) thread_id: ThreadId,
+    /// Quiet checks are generated in the first this-many qsearch plies not in check.
+    pub(crate) qsearch_checks: u8,
+    /// Endgame tables probed at the root and at every interior node they cover.
+    pub(crate) tablebases: Option<Arc<Tablebases>>,
+    /// Pruning and reduction constants.
+    pub(crate) params: Arc<SearchParams>,
+    /// When non-empty, the only root moves searched. Used to score a given move.
+    pub(crate) root_moves: Vec<Move>,
+    /// Record the capture line behind each quiescence score. Only the Texel tuner's
+    /// [`quiesce_pv`](Search::quiesce_pv) wants it; the main search's PV stops at the horizon.
+    qsearch_pv: bool,
     start: Instant,
     soft_limit: Option<Duration>,
     hard_limit: Option<Duration>,
@@ -144,6 +441,11 @@ pub(crate) struct Search<'a> {
     max_depth: u8,
 
     pub(crate) nodes: u64,
+    /// Deepest ply visited in the current iteration.
+    seldepth: u32,
+    /// Root move under search and its 1-based number, for progress reports.
+    currmove: Move,
+    currmove_number: u32,
     /// Principal variation triangle; `pv[ply][0..pv_len[ply]]` is the line rooted at ply.
     pv: Vec<[Move; MAX_PLY]>,
     pv_len: [usize; MAX_PLY],
@@ -160,6 +462,11 @@ pub(crate) struct Search<'a> {
     excluded_at_ply: [Move; MAX_PLY + 1],
     /// (piece, to-square) of the most recent move at each ply, for countermove lookup.
     prev_move_info: [Option<(Piece, u8)>; MAX_PLY + 1],
+    /// Root moves already reported as earlier MultiPV lines this iteration; skipped at ply 0.
+    root_excluded: Vec<Move>,
+    /// Contempt, from the root side's perspective: draws score `-contempt` for the side
+    /// that was on move at the root.
+    contempt: i32,
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -168,6 +475,8 @@ struct PlyMeta {
     gave_check: bool,
     /// True when this move was a capture (breaks repetition chains for irreversible moves).
     was_capture: bool,
+    /// Plies since the last capture, including this move. Drives the natural move limit.
+    quiet_plies: u16,
 }
 
 impl<'a> Search<'a> {
@@ -178,12 +487,20 @@ impl<'a> Search<'a> {
             history: History::new(),
             stop,
             thread_id: 0,
+            qsearch_checks: DEFAULT_QSEARCH_CHECKS,
+            tablebases: None,
+            params: Arc::clone(&DEFAULT_SEARCH_PARAMS),
+            root_moves: Vec::new(),
+            qsearch_pv: false,
             start: Instant::now(),
             soft_limit: None,
             hard_limit: None,
             node_limit: None,
             max_depth: MAX_SEARCH_DEPTH,
             nodes: 0,
+            seldepth: 0,
+            currmove: Move::NULL,
+            currmove_number: 0,
             pv: vec![[Move::NULL; MAX_PLY]; MAX_PLY + 1],
             pv_len: [0; MAX_PLY],
             key_stack: Vec::with_capacity(256),
@@ -191,20 +508,30 @@ impl<'a> Search<'a> {
             static_evals: [0; MAX_PLY + 1],
             excluded_at_ply: [Move::NULL; MAX_PLY + 1],
             prev_move_info: [None; MAX_PLY + 1],
+            root_excluded: Vec::new(),
+            contempt: 0,
         }
     }
 
     /// Pre-populate the repetition history with zobrist keys seen prior to the current
     /// search. Callers pass `Engine::game_key_history()` here so 3-fold draws that span
-    /// across search invocations are detected correctly.
-    pub(crate) fn seed_game_history(&mut self, keys: &[u64]) {
+    /// across search invocations are detected correctly, together with one capture flag
+    /// per key so the natural move limit counts from the last real capture.
+    pub(crate) fn seed_game_history(&mut self, keys: &[u64], captures: impl IntoIterator<Item = bool>) {
         self.key_stack.clear();
         self.key_stack.extend_from_slice(keys);
-        // We don't have per-move metadata for historical plies — treat them conservatively
-        // as quiet, non-checking moves. This is a heuristic: the worst that happens is we
+        // We don't have check metadata for historical plies — treat them conservatively
+        // as non-checking moves. This is a heuristic: the worst that happens is we
         // occasionally fail to classify an older perpetual cycle, which is acceptable.
         self.meta_stack.clear();
-        self.meta_stack.resize(keys.len(), PlyMeta::default());
+        for was_capture in captures.into_iter().take(keys.len()) {
+            let meta = self.next_meta(false, was_capture);
+            self.meta_stack.push(meta);
+        }
+        while self.meta_stack.len() < keys.len() {
+            let meta = self.next_meta(false, false);
+            self.meta_stack.push(meta);
+        }
     }
 
     /// Run the iterative-deepening search. `callback` is invoked once per completed
@@ -216,62 +543,101 @@ impl<'a> Search<'a> {
         self.hard_limit = limits.max_time;
         self.soft_limit = limits.max_time.map(|t| t / 2 + t / 8); // ~62.5% of budget
         self.node_limit = limits.max_nodes;
+        self.contempt = limits.contempt.unwrap_or(self.pos.eval_params().draw_value);
         self.tt.bump_age();
         self.history.clear();
 
-        let mut prev_score = 0i32;
+        let multipv = (limits.multipv as usize).min(self.root_move_count()).max(1);
+        let mut prev_scores: Vec<i32> = Vec::with_capacity(multipv);
         let mut best_info = SearchInfo::default();
+        if multipv == 1
+            && self.root_moves.is_empty()
+            && let Some(info) = self.tablebase_root()
+        {
+            callback(&info);
+            return info;
+        }
 
-        for depth in 1..=self.max_depth {
+        'iterate: for depth in 1..=self.max_depth {
             // Lazy SMP: helper threads skip select depths so that workers explore the
             // search tree at staggered horizons instead of redundantly racing the main
             // thread. Helpers always run depth 1 (cheap and seeds the TT) and any depth
             // that survives the skip pattern.
-            if depth > 1 && should_skip_depth(self.thread_id, depth) {
+            if depth > 1 && should_skip_depth(&self.params, self.thread_id, depth) {
                 continue;
             }
 
-            self.pv_len = [0; MAX_PLY];
-
-            let mut alpha = -INF;
-            let mut beta = INF;
-            // Per-thread aspiration delta dispersion — helpers cast a slightly wider net
-            // so that they don't all re-search on the same fail-high/low boundary.
-            let aspiration_seed = ASPIRATION_DELTA + (self.thread_id as i32) * 4;
-            let mut delta = aspiration_seed;
+            // MultiPV: search the root once per line, each time excluding the first moves
+            // of the lines already found.
+            let mut lines: Vec<RootLine> = Vec::with_capacity(multipv);
+            self.root_excluded.clear();
+            self.seldepth = 0;
+            for idx in 0..multipv {
+                self.pv_len = [0; MAX_PLY];
+
+                let mut alpha = -INF;
+                let mut beta = INF;
+                // Per-thread aspiration delta dispersion — helpers cast a slightly wider net
+                // so that they don't all re-search on the same fail-high/low boundary.
+                let aspiration_seed = self.params.aspiration_delta + (self.thread_id as i32) * 4;
+                let mut delta = aspiration_seed;
+
+                if depth >= 5
+                    && let Some(&prev_score) = prev_scores.get(idx)
+                {
+                    alpha = (prev_score - aspiration_seed).max(-INF);
+                    beta = (prev_score + aspiration_seed).min(INF);
+                }
 
-            if depth >= 5 {
-                alpha = (prev_score - aspiration_seed).max(-INF);
-                beta = (prev_score + aspiration_seed).min(INF);
-            }
+                let score = loop {
+                    let s = self.alpha_beta(alpha, beta, depth as i32, 0, false);
+                    if self.stop_requested() && depth > 1 {
+                        break s;
+                    }
+                    let bound = if s <= alpha {
+                        beta = (alpha + beta) / 2;
+                        alpha = (alpha - delta).max(-INF);
+                        delta += delta / 2;
+                        ScoreBound::Upper
+                    } else if s >= beta {
+                        beta = (beta + delta).min(INF);
+                        delta += delta / 2;
+                        ScoreBound::Lower
+                    } else {
+                        break s;
+                    };
+                    // Report the failed window on the principal line so GUIs can show the
+                    // score moving while the re-search runs.
+                    if idx == 0 {
+                        let pv = match self.pv_len[0] {
+                            0 => best_info.pv.clone(),
+                            len => self.pv[0][..len].to_vec(),
+                        };
+                        callback(&self.info(depth, s, pv, Vec::new(), bound));
+                    }
+                };
 
-            let score = loop {
-                let s = self.alpha_beta(alpha, beta, depth as i32, 0, false);
                 if self.stop_requested() && depth > 1 {
-                    break s;
-                }
-                if s <= alpha {
-                    beta = (alpha + beta) / 2;
-                    alpha = (alpha - delta).max(-INF);
-                    delta += delta / 2;
-                } else if s >= beta {
-                    beta = (beta + delta).min(INF);
-                    delta += delta / 2;
-                } else {
-                    break s;
+                    break 'iterate;
                 }
-            };
 
-            if self.stop_requested() && depth > 1 {
-                break;
+                let pv = self.pv[0][..self.pv_len[0]].to_vec();
+                let first = pv.first().copied();
+                lines.push(RootLine { score, pv });
+                match first {
+                    Some(mv) => self.root_excluded.push(mv),
+                    None => break,
+                }
             }
-
-            prev_score = score;
-            let pv_line = self.pv[0][..self.pv_len[0]].to_vec();
-            let best_move = pv_line.first().copied();
-            let elapsed = self.start.elapsed();
-            let nps = if elapsed.as_micros() > 0 { (self.nodes as f64 / elapsed.as_secs_f64()) as u64 } else { 0 };
-            best_info = SearchInfo { depth, score, best_move, pv: pv_line, nodes: self.nodes, time: elapsed, nps };
+            self.root_excluded.clear();
+            // Later lines occasionally outscore earlier ones once the best move is excluded
+            // from the window; keep the report ordered.
+            lines.sort_by_key(|line| std::cmp::Reverse(line.score));
+            prev_scores = lines.iter().map(|line| line.score).collect();
+
+            let (score, pv_line) = (lines[0].score, lines[0].pv.clone());
+            best_info = self.info(depth, score, pv_line, lines, ScoreBound::Exact);
+            let elapsed = best_info.time;
             callback(&best_info);
 
             if score.abs() > WIN_VALUE {
@@ -289,6 +655,58 @@ impl<'a> Search<'a> {
         best_info
     }
 
+    /// Progress snapshot for `depth` with the given principal line.
+    fn info(&self, depth: u8, score: i32, pv: Vec<Move>, lines: Vec<RootLine>, bound: ScoreBound) -> SearchInfo {
+        let time = self.start.elapsed();
+        let nps = if time.as_micros() > 0 { (self.nodes as f64 / time.as_secs_f64()) as u64 } else { 0 };
+        SearchInfo {
+            depth,
+            score,
+            best_move: pv.first().copied(),
+            pv,
+            nodes: self.nodes,
+            time,
+            nps,
+            lines,
+            hashfull: self.tt.hashfull(),
+            mate: None,
+            seldepth: self.seldepth.min(u8::MAX as u32) as u8,
+            mate_in: mate_in(score),
+            bound,
+            currmove: Some(self.currmove).filter(|mv| !mv.is_null()),
+            currmove_number: self.currmove_number,
+        }
+    }
+
+    /// Number of legal moves at the root; caps the MultiPV line count.
+    fn root_move_count(&self) -> usize {
+        let mut ml = MoveList::new();
+        generate_legal(self.pos, &mut ml);
+        match self.root_moves.is_empty() {
+            true => ml.len(),
+            false => ml.iter().filter(|mv| self.root_moves.contains(mv)).count(),
+        }
+    }
+
+    /// Whether root move `mv` is left out of this search: already reported as a MultiPV
+    /// line, or outside a `root_moves` restriction.
+    fn skip_at_root(&self, mv: Move) -> bool {
+        self.root_excluded.contains(&mv) || (!self.root_moves.is_empty() && !self.root_moves.contains(&mv))
+    }
+
+    /// Bare quiescence search from the current position with a full window. Returns the
+    /// score (side-to-move perspective) and the capture line leading to the quiet leaf it
+    /// was taken from. Used by the Texel tuner, which evaluates that leaf with its own
+    /// parameter vector.
+    pub(crate) fn quiesce_pv(&mut self) -> (i32, Vec<Move>) {
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.qsearch_pv = true;
+        let score = self.quiesce(-INF, INF, 0, 0);
+        self.qsearch_pv = false;
+        (score, self.pv[0][..self.pv_len[0]].to_vec())
+    }
+
     // ------------------------------------------------------------
     // Time / node checks
     // ------------------------------------------------------------
@@ -386,7 +804,8 @@ impl<'a> Search<'a> {
     }
 
     /// Map repetition flags to a search score. `ply` scales the penalty so that
-    /// mate-distance scores decay cleanly along the PV.
+    /// mate-distance scores decay cleanly along the PV. Plain repetitions (and cycles where
+    /// both sides check perpetually) are draws scored with the configured contempt.
     fn rep_value(&self, flags: u32, ply: u32) -> i32 {
         let ban = ply as i32 - BAN_VALUE;
         let mut v = 0;
@@ -396,7 +815,62 @@ impl<'a> Search<'a> {
         if flags & 4 != 0 {
             v -= ban; // opponent is the chaser → big positive for us
         }
-        if v == 0 { draw_value(ply) } else { v }
+        if v == 0 { self.draw_score(ply) } else { v }
+    }
+
+    /// Score of a drawn node at `ply`, seen by the side to move there.
+    #[inline]
+    fn draw_score(&self, ply: u32) -> i32 { draw_value(self.contempt, ply) }
+
+    #[inline]
+    fn probe_tablebases(&self) -> Option<TbProbe> {
+        let tb = self.tablebases.as_deref()?;
+        if self.pos.occupancy().popcount() > tb.max_pieces() {
+            return None;
+        }
+        tb.probe(self.pos)
+    }
+
+    /// Search score of a tablebase result `ply` plies from the root. Mates too distant for
+    /// the mate-score band are reported just below it, as certain but unranked wins.
+    fn tablebase_score(&self, probe: TbProbe, ply: u32) -> i32 {
+        let distance = ply as i32 + probe.dtm as i32;
+        let win = if MATE_VALUE - distance > BAN_VALUE { MATE_VALUE - distance } else { WIN_VALUE - 1 };
+        match probe.wdl {
+            Wdl::Win => win,
+            Wdl::Loss => -win,
+            Wdl::Draw => self.draw_score(ply),
+        }
+    }
+
+    /// A root won or lost by the tables is answered from them, with the line walked to
+    /// mate. Drawn roots are searched normally; the tree probes keep the draw in hand.
+    fn tablebase_root(&self) -> Option<SearchInfo> {
+        let tb = self.tablebases.as_deref()?;
+        let probe = self.probe_tablebases()?;
+        if probe.wdl == Wdl::Draw {
+            return None;
+        }
+        let (best, _) = tb.best_move(self.pos)?;
+        let mut pv = vec![best];
+        let mut line = self.pos.clone();
+        line.make_move(best);
+        while pv.len() < probe.dtm as usize
+            && let Some((mv, _)) = tb.best_move(&line)
+        {
+            pv.push(mv);
+            line.make_move(mv);
+        }
+        let score = self.tablebase_score(probe, 0);
+        let lines = vec![RootLine { score, pv: pv.clone() }];
+        Some(self.info(1, score, pv, lines, ScoreBound::Exact))
+    }
+
+    /// Metadata for a move about to be pushed onto `meta_stack`.
+    #[inline]
+    fn next_meta(&self, gave_check: bool, was_capture: bool) -> PlyMeta {
+        let quiet_plies = if was_capture { 0 } else { self.meta_stack.last().map_or(0, |m| m.quiet_plies) + 1 };
+        PlyMeta { gave_check, was_capture, quiet_plies }
     }
 
     // ------------------------------------------------------------
@@ -411,10 +885,11 @@ impl<'a> Search<'a> {
 
         // Leaf → quiesce.
         if depth <= 0 {
-            return self.quiesce(alpha, beta, ply);
+            return self.quiesce(alpha, beta, ply, self.qsearch_checks);
         }
 
         self.nodes += 1;
+        self.seldepth = self.seldepth.max(ply);
         if self.nodes & 0xfff == 0 {
             self.check_stop();
         }
@@ -424,7 +899,8 @@ impl<'a> Search<'a> {
 
         let is_pv = beta - alpha > 1;
         let us = self.pos.side_to_move();
-        let in_check = self.pos.is_in_check(us);
+        let check_info = self.pos.check_info();
+        let in_check = check_info.checkers.any();
 
         // Mate distance pruning.
         alpha = alpha.max(-MATE_VALUE + ply as i32);
@@ -441,6 +917,12 @@ impl<'a> Search<'a> {
             if flags != 0 {
                 return self.rep_value(flags, ply);
             }
+            if self.meta_stack.last().is_some_and(|m| m.quiet_plies >= MOVE_LIMIT_PLIES) {
+                return self.draw_score(ply);
+            }
+            if let Some(probe) = self.probe_tablebases() {
+                return self.tablebase_score(probe, ply);
+            }
         }
 
         // ---------- Excluded-move context (for Singular Extensions) ----------
@@ -489,7 +971,7 @@ impl<'a> Search<'a> {
 
         // ---------- Reverse futility pruning (static null) ----------
         if !is_pv && !in_check && depth <= 6 {
-            let margin = if improving { 120 * depth } else { 160 * depth };
+            let margin = depth * if improving { self.params.rfp_margin_improving } else { self.params.rfp_margin };
             if static_eval.saturating_sub(margin) >= beta && static_eval < WIN_VALUE {
                 return static_eval - margin;
             }
@@ -497,9 +979,9 @@ impl<'a> Search<'a> {
 
         // ---------- Razoring ----------
         if !is_pv && !in_check && depth <= 3 {
-            let margin = 200 + 80 * depth;
+            let margin = self.params.razor_base + self.params.razor_margin * depth;
             if static_eval + margin < alpha {
-                let q = self.quiesce(alpha, beta, ply);
+                let q = self.quiesce(alpha, beta, ply, self.qsearch_checks);
                 if q < alpha {
                     return q;
                 }
@@ -531,12 +1013,13 @@ impl<'a> Search<'a> {
             && !no_null
             && excluded.is_null()
             && depth >= 3
-            && self.pos.material(us) > NULL_OKAY_MARGIN
+            && self.pos.material(us) > self.pos.eval_params().null_okay_margin
         {
-            let r = 2 + depth / 4; // classical 2 + depth/4 reduction
+            let r = self.params.null_move_base + depth / self.params.null_move_divisor;
             let null_info = self.pos.make_null();
             self.key_stack.push(key);
-            self.meta_stack.push(PlyMeta::default());
+            let meta = self.next_meta(false, false);
+            self.meta_stack.push(meta);
             let score = -self.alpha_beta(-beta, -beta + 1, depth - r - 1, ply + 1, true);
             self.meta_stack.pop();
             self.key_stack.pop();
@@ -563,7 +1046,12 @@ impl<'a> Search<'a> {
 
         // Futility base for quiet-move pruning inside the move loop.
         let futility_base = if !is_pv && !in_check && depth <= 3 {
-            let margin = if improving { 100 * depth + 75 } else { 70 * depth + 50 };
+            let p = &self.params;
+            let margin = if improving {
+                p.futility_margin_improving * depth + p.futility_base_improving
+            } else {
+                p.futility_margin * depth + p.futility_base
+            };
             Some(static_eval + margin)
         } else {
             None
@@ -571,7 +1059,7 @@ impl<'a> Search<'a> {
         // Late Move Pruning limit — more generous on the improving trajectory, aggressive
         // when we're stagnating or falling behind.
         let lmp_limit: Option<u32> = if !is_pv && !in_check && depth <= 5 {
-            let base = 5 + depth * depth;
+            let base = self.params.lmp_base + depth * depth;
             Some(if improving { base as u32 } else { (base / 2) as u32 })
         } else {
             None
@@ -584,7 +1072,10 @@ impl<'a> Search<'a> {
         let mut searched_any = false;
 
         while let Some(mv) = picker.next(self.pos, &self.history) {
-            if mv == excluded {
+            if mv == excluded || (ply == 0 && self.skip_at_root(mv)) {
+                continue;
+            }
+            if !self.pos.legal(mv, &check_info) {
                 continue;
             }
             let is_capture_pre = self.pos.piece_at(mv.dst()).is_some();
@@ -611,15 +1102,15 @@ impl<'a> Search<'a> {
             if excluded.is_null()
                 && mv == tt_move
                 && !mv.is_null()
-                && depth >= 8
+                && depth >= self.params.singular_min_depth
                 && ply > 0
                 && let Some(hit) = tt_entry
                 && hit.bound != Bound::Alpha
-                && hit.depth >= depth - 3
+                && hit.depth >= depth - self.params.singular_tt_depth
             {
                 let tt_value = mate_score_from_tt(hit.value, ply);
                 if tt_value.abs() < WIN_VALUE {
-                    let singular_beta = (tt_value - 2 * depth).max(-MATE_VALUE + 1);
+                    let singular_beta = (tt_value - self.params.singular_margin * depth).max(-MATE_VALUE + 1);
                     let singular_depth = (depth - 1) / 2;
                     if ply_idx <= MAX_PLY {
                         self.excluded_at_ply[ply_idx] = mv;
@@ -637,15 +1128,15 @@ impl<'a> Search<'a> {
             // Capture piece + dst info BEFORE making the move — used for prev_move_info.
             let mover_piece = self.pos.piece_at(mv.src());
             let undo = self.pos.make_move(mv);
-            if self.pos.is_in_check(us) {
-                self.pos.undo_move(mv, undo);
-                continue;
-            }
             // Prefetch the child-position TT cluster while we do legality / book-keeping
             // work. By the time the recursive call probes, the line should be in L1.
             self.tt.prefetch(self.pos.zobrist_key());
             move_count += 1;
             searched_any = true;
+            if ply == 0 {
+                self.currmove = mv;
+                self.currmove_number = move_count;
+            }
             let is_capture = undo.captured.is_some();
             let gives_check = self.pos.is_in_check(us.flip());
 
@@ -657,7 +1148,7 @@ impl<'a> Search<'a> {
             // ---------- LMR ----------
             let mut reduction = 0;
             if depth >= 3 && move_count > 3 && !is_capture && !gives_check && !in_check {
-                reduction = lmr(depth, move_count, is_pv).min(depth - 1);
+                reduction = lmr(&self.params, depth, move_count, is_pv).min(depth - 1);
                 // Extra reduction on a non-improving trajectory.
                 if !improving {
                     reduction += 1;
@@ -666,7 +1157,8 @@ impl<'a> Search<'a> {
             }
 
             self.key_stack.push(key);
-            self.meta_stack.push(PlyMeta { gave_check: gives_check, was_capture: is_capture });
+            let meta = self.next_meta(gives_check, is_capture);
+            self.meta_stack.push(meta);
 
             // ---------- PVS ----------
             let new_depth = depth - 1 + extension;
@@ -726,18 +1218,25 @@ impl<'a> Search<'a> {
             return -MATE_VALUE + ply as i32;
         }
 
-        if !self.stop_requested() && excluded.is_null() {
+        // A root searched with MultiPV exclusions or a move restriction only bounds the
+        // remaining moves; storing it would mislead later TT probes.
+        if !self.stop_requested()
+            && excluded.is_null()
+            && (ply > 0 || (self.root_excluded.is_empty() && self.root_moves.is_empty()))
+        {
             self.tt.store(key, best_move, best_value, depth, bound, ply);
         }
         best_value
     }
 
     // ------------------------------------------------------------
-    // Quiescence search (captures only; check evasions handled fully)
+    // Quiescence search (captures, plus quiet checks for the first `checks` plies;
+    // check evasions handled fully)
     // ------------------------------------------------------------
 
-    fn quiesce(&mut self, mut alpha: i32, beta: i32, ply: u32) -> i32 {
+    fn quiesce(&mut self, mut alpha: i32, beta: i32, ply: u32, checks: u8) -> i32 {
         self.nodes += 1;
+        self.seldepth = self.seldepth.max(ply);
         if self.nodes & 0xfff == 0 {
             self.check_stop();
         }
@@ -748,9 +1247,12 @@ impl<'a> Search<'a> {
         if (ply as usize) >= MAX_PLY {
             return evaluate(self.pos);
         }
+        if self.qsearch_pv {
+            self.pv_len[ply as usize] = 0;
+        }
 
-        let us = self.pos.side_to_move();
-        let in_check = self.pos.is_in_check(us);
+        let check_info = self.pos.check_info();
+        let in_check = check_info.checkers.any();
 
         let stand_pat;
         let see_prune = !in_check;
@@ -762,8 +1264,9 @@ impl<'a> Search<'a> {
                 return stand_pat;
             }
             // Global delta pruning: if even capturing the most valuable opposing piece
-            // can't lift us to alpha, there's no point looking at any capture.
-            if stand_pat + crate::see::SEE_ROOK + 2 * crate::see::SEE_PAWN < alpha {
+            // can't lift us to alpha, there's no point looking at any capture. Not while
+            // quiet checks are searched here: a mating check is worth more than any capture.
+            if checks == 0 && stand_pat + crate::see::SEE_ROOK + 2 * crate::see::SEE_PAWN < alpha {
                 return alpha;
             }
             if stand_pat > alpha {
@@ -776,7 +1279,7 @@ impl<'a> Search<'a> {
         // post-filter pass.
         let mut moves = MoveList::new();
         if in_check {
-            generate_pseudo(self.pos, &mut moves);
+            generate_evasions(self.pos, &mut moves);
         } else {
             generate_captures(self.pos, &mut moves);
         }
@@ -790,7 +1293,7 @@ impl<'a> Search<'a> {
             let victim = self.pos.piece_at(mv.dst());
             if !in_check {
                 let victim_val = victim.map(|p| crate::see::see_value(p.kind())).unwrap_or(0);
-                if stand_pat + victim_val + 150 < alpha {
+                if stand_pat + victim_val + self.params.qsearch_delta < alpha {
                     continue;
                 }
             }
@@ -806,6 +1309,19 @@ impl<'a> Search<'a> {
             buffer[list_len] = (*mv, score);
             list_len += 1;
         }
+        // Quiet checks go after every capture; like captures, ones that lose the piece
+        // outright are skipped.
+        if !in_check && checks > 0 {
+            let mut quiet_checks = MoveList::new();
+            generate_quiet_checks(self.pos, &mut quiet_checks);
+            for &mv in quiet_checks.iter() {
+                if see(self.pos, mv) < 0 || list_len == buffer.len() {
+                    continue;
+                }
+                buffer[list_len] = (mv, -1);
+                list_len += 1;
+            }
+        }
         // Selection sort by score descending. The index-based form is the natural shape of
         // selection sort; iterator gymnastics here would only obscure intent.
         #[allow(clippy::needless_range_loop)]
@@ -824,13 +1340,14 @@ impl<'a> Search<'a> {
         let mut best = if in_check { -INF } else { stand_pat };
         for entry in &buffer[..list_len] {
             let mv = entry.0;
-            let undo = self.pos.make_move(mv);
-            if self.pos.is_in_check(us) {
-                self.pos.undo_move(mv, undo);
+            // Evasions are already legal.
+            if !in_check && !self.pos.legal(mv, &check_info) {
                 continue;
             }
+            let undo = self.pos.make_move(mv);
             self.tt.prefetch(self.pos.zobrist_key());
-            let score = -self.quiesce(-beta, -alpha, ply + 1);
+            let child_checks = if in_check { checks } else { checks.saturating_sub(1) };
+            let score = -self.quiesce(-beta, -alpha, ply + 1, child_checks);
             self.pos.undo_move(mv, undo);
             if self.stop_requested() {
                 return 0;
@@ -842,6 +1359,9 @@ impl<'a> Search<'a> {
                 }
                 if score > alpha {
                     alpha = score;
+                    if self.qsearch_pv {
+                        self.copy_pv(ply as usize, mv);
+                    }
                 }
             }
         }
@@ -880,13 +1400,118 @@ fn piece_weight(kind: PieceType) -> i32 {
 }
 
 #[inline]
-fn lmr(depth: i32, move_count: u32, is_pv: bool) -> i32 {
-    // Log-based reduction: r ≈ 0.6 + ln(depth) * ln(movecount) / 2.35 (minus 1 on PV nodes).
+fn lmr(params: &SearchParams, depth: i32, move_count: u32, is_pv: bool) -> i32 {
+    // Log-based reduction: r ≈ base + ln(depth) * ln(movecount) / divisor (minus 1 on PV
+    // nodes); the defaults give 0.6 + … / 2.35.
     let d = depth.max(1) as f32;
     let m = move_count.max(1) as f32;
-    let mut r = 0.6 + (d.ln() * m.ln() / 2.35);
+    let mut r = params.lmr_base as f32 / 100.0 + (d.ln() * m.ln() / (params.lmr_divisor as f32 / 100.0));
     if is_pv {
         r -= 1.0;
     }
     (r as i32).max(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search_with_history(fen: &str, captures: &[bool], limits: Limits) -> SearchInfo {
+        let mut pos = Position::from_fen(fen).unwrap();
+        let tt = Arc::new(TranspositionTable::new(1 << 20));
+        let mut search = Search::new(&mut pos, tt, Arc::new(AtomicBool::new(false)));
+        // Distinct dummy keys so no repetition is detected; only the capture flags matter.
+        let keys: Vec<u64> = (1..=captures.len() as u64).collect();
+        search.seed_game_history(&keys, captures.iter().copied());
+        search.run(limits, |_| {})
+    }
+
+    #[test]
+    fn draw_score_follows_root_perspective() {
+        let mut pos = Position::from_fen(crate::fen::STARTING_FEN).unwrap();
+        let tt = Arc::new(TranspositionTable::new(1 << 20));
+        let mut search = Search::new(&mut pos, tt, Arc::new(AtomicBool::new(false)));
+        search.contempt = 30;
+        assert_eq!(search.draw_score(2), -30);
+        assert_eq!(search.draw_score(3), 30);
+    }
+
+    #[test]
+    fn pv_stops_at_the_horizon() {
+        // Both sides have captures at every leaf; none of them belong in a depth-1 PV.
+        let mut pos = Position::from_fen("3k5/9/9/9/2r1p4/2N1P4/9/9/9/4K4 w").unwrap();
+        let tt = Arc::new(TranspositionTable::new(1 << 20));
+        let search = Search::new(&mut pos, tt, Arc::new(AtomicBool::new(false)));
+        assert_eq!(search.run(Limits::new().depth(1), |_| {}).pv.len(), 1);
+    }
+
+    #[test]
+    fn quiet_mating_checks_survive_delta_pruning() {
+        // Red is two rooks down, far below alpha, but Ra0-d0 mates.
+        let mut pos = Position::from_fen("3k3rr/9/9/9/9/9/9/9/9/R3K4 w").unwrap();
+        let alpha = evaluate(&pos) + crate::see::SEE_ROOK + 2 * crate::see::SEE_PAWN + 1;
+        let tt = Arc::new(TranspositionTable::new(1 << 20));
+        let mut search = Search::new(&mut pos, tt, Arc::new(AtomicBool::new(false)));
+        assert!(search.quiesce(alpha, INF, 0, 1) > WIN_VALUE);
+        assert_eq!(search.quiesce(alpha, INF, 0, 0), alpha);
+    }
+
+    #[test]
+    fn move_limit_scores_as_contempt_draw() {
+        const KR_VS_K: &str = "3k5/9/9/9/9/9/9/9/9/R4K3 w";
+        let quiet = [false; MOVE_LIMIT_PLIES as usize];
+        let info = search_with_history(KR_VS_K, &quiet, Limits::new().depth(3).contempt(50));
+        assert_eq!(info.score, -50);
+        let info = search_with_history(KR_VS_K, &quiet, Limits::new().depth(3).contempt(-50));
+        assert_eq!(info.score, 50);
+
+        // A capture on the last played move restarts the count, so the rook is worth its value.
+        let mut reset = quiet;
+        reset[MOVE_LIMIT_PLIES as usize - 1] = true;
+        let info = search_with_history(KR_VS_K, &reset, Limits::new().depth(3).contempt(50));
+        assert!(info.score > 100, "score {}", info.score);
+    }
+
+    #[test]
+    fn mate_in_counts_moves_for_both_sides() {
+        assert_eq!(mate_in(MATE_VALUE - 1), Some(1));
+        assert_eq!(mate_in(MATE_VALUE - 3), Some(2));
+        assert_eq!(mate_in(-MATE_VALUE + 2), Some(-1));
+        assert_eq!(mate_in(-MATE_VALUE + 4), Some(-2));
+        assert_eq!(mate_in(WIN_VALUE - 1), None);
+        assert_eq!(mate_in(0), None);
+    }
+
+    #[test]
+    fn reports_progress_fields() {
+        let mut infos = Vec::new();
+        let mut pos = Position::from_fen("3k5/9/9/9/9/9/9/9/9/R3K4 w").unwrap();
+        let tt = Arc::new(TranspositionTable::new(1 << 20));
+        let search = Search::new(&mut pos, tt, Arc::new(AtomicBool::new(false)));
+        let info = search.run(Limits::new().depth(4), |info| infos.push(info.clone()));
+        assert_eq!(info.mate_in, Some(1));
+        assert_eq!(info.bound, ScoreBound::Exact);
+        for info in infos.iter().filter(|info| info.bound == ScoreBound::Exact) {
+            assert!(info.seldepth >= info.depth);
+            assert!(info.currmove.is_some());
+            assert!(info.currmove_number >= 1);
+        }
+    }
+
+    #[test]
+    fn search_params_text_roundtrip() {
+        let mut params = SearchParams { rfp_margin: 150, lmr_divisor: 250, ..SearchParams::default() };
+        params.skip_phase[3] = 2;
+        assert_eq!(params.to_string().parse::<SearchParams>().unwrap(), params);
+        assert_eq!("qsearch_delta 120 # tuned".parse::<SearchParams>().unwrap().qsearch_delta, 120);
+        for bad in ["null_move_divisor 0", "skip_size 0", "lmr_base x", "aspiration_delta", "margin 3"] {
+            assert!(matches!(bad.parse::<SearchParams>(), Err(ChessAIError::BadSearchParams { line: 1, .. })), "{bad}");
+        }
+
+        let wild =
+            SearchParams { null_move_divisor: 0, lmr_divisor: -3, skip_size: [0; 20], ..SearchParams::default() };
+        let clamped = wild.clamped();
+        assert_eq!((clamped.null_move_divisor, clamped.lmr_divisor, clamped.skip_size), (2, 100, [1; 20]));
+        assert_eq!(SearchParams::default().clamped(), SearchParams::default());
+    }
+}
@@ -72,8 +72,24 @@ pub fn attackers_to(pos: &Position, dst: Square, occ: BitBoard) -> BitBoard {
     attackers
 }
 
+/// Threat summary for one side, returned by [`Position::threats`]. Each field is a set of
+/// that side's own pieces (never the king).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Threats {
+    /// Pieces the opponent wins material from by capturing now: attacked and under-defended,
+    /// judged by the SEE of the cheapest capture.
+    pub hanging: BitBoard,
+    /// Pieces pinned against the king by a rook or the flying-general file, or standing on
+    /// the leg of a knight that would otherwise give check.
+    pub pinned: BitBoard,
+    /// Pieces that are one of two screens between the king and an enemy cannon; moving
+    /// either lets the cannon give check.
+    pub cannon_pinned: BitBoard,
+}
+
+/// Cheapest piece of `color` among `attackers`, with its kind.
 #[inline]
-fn least_valuable(pos: &Position, attackers: BitBoard, color: Color) -> Option<(Square, PieceType)> {
+pub(crate) fn least_valuable(pos: &Position, attackers: BitBoard, color: Color) -> Option<(Square, PieceType)> {
     for kind in [
         PieceType::Pawn,
         PieceType::Cannon,
@@ -177,6 +193,22 @@ mod tests {
         assert_eq!(s, SEE_KNIGHT - SEE_ROOK);
     }
 
+    #[test]
+    fn threats_report_hanging_and_pinned_pieces() {
+        // Red rook e3 is pinned by the black rook e9 (and hangs to it); the red cannon d4
+        // hangs to the rook on d9; knight b0 and bishop c0 are the two screens between the
+        // black cannon a0 and the red king, and the cannon can take the bishop for free.
+        let fen = "3rrk3/9/9/9/9/3C5/4R4/9/9/cNB1K4 w";
+        let pos = Position::from_fen(fen).unwrap();
+        let t = pos.threats(Color::Red);
+        let sq = |s: &str| BitBoard::from_square(Square::from_iccs(s).unwrap());
+        assert_eq!(t.hanging, sq("c0") | sq("d4") | sq("e3"));
+        assert_eq!(t.pinned, sq("e3"));
+        assert_eq!(t.cannon_pinned, sq("b0") | sq("c0"));
+        assert_eq!(pos.see(M::from_iccs("d9-d4").unwrap()), SEE_CANNON);
+        assert_eq!(pos.threats(Color::Black), Threats::default());
+    }
+
     #[test]
     fn pawn_for_rook_is_a_winning_trade() {
         // Black pawn defended by nothing — red rook picks it up for free.
@@ -0,0 +1,62 @@
+//! Local engine-vs-engine games. Both engines follow the game move by move; the one whose
+//! side is to move searches, and [`Engine::game_status`] adjudicates.
+
+use crate::color::Color;
+use crate::engine::Engine;
+use crate::engine::GameStatus;
+use crate::error::ChessAIError;
+use crate::limits::Limits;
+use crate::mv::Move;
+use crate::search::SearchInfo;
+use crate::util::SplitMix64;
+
+/// `plies` random legal moves from the starting position, stopping early should the game
+/// end on the way.
+pub(crate) fn random_opening(rng: &mut SplitMix64, plies: u8) -> Vec<Move> {
+    let mut board = Engine::builder().hash_size(0).use_book(false).build();
+    let mut line = Vec::with_capacity(plies as usize);
+    for _ in 0..plies {
+        let legal = board.legal_moves();
+        if legal.is_empty() || board.game_status().is_over() {
+            break;
+        }
+        let mv = legal[(rng.next_u64() % legal.len() as u64) as usize];
+        board.make_move(mv);
+        line.push(mv);
+    }
+    line
+}
+
+/// Play one game from `fen` after `opening`, `red` playing red. Every move is searched
+/// with `limits` and passed to `on_move` together with the engine that chose it, still
+/// at the position it was chosen in. Returns the final, finished status.
+pub(crate) fn play_game(
+    red: &mut Engine,
+    black: &mut Engine,
+    fen: &str,
+    opening: &[Move],
+    limits: Limits,
+    mut on_move: impl FnMut(&Engine, &SearchInfo, Move),
+) -> Result<GameStatus, ChessAIError> {
+    for engine in [&mut *red, &mut *black] {
+        engine.set_fen(fen)?;
+        for &mv in opening {
+            engine.make_move(mv);
+        }
+    }
+    loop {
+        let status = red.game_status();
+        if status.is_over() {
+            return Ok(status);
+        }
+        let mover = if red.side_to_move() == Color::Red { &mut *red } else { &mut *black };
+        let info = mover.search(limits);
+        let mv = match info.best_move {
+            Some(mv) => mv,
+            None => mover.legal_moves()[0],
+        };
+        on_move(mover, &info, mv);
+        red.make_move(mv);
+        black.make_move(mv);
+    }
+}
@@ -0,0 +1,155 @@
+//! Strength limiting for casual play. A skill level below [`MAX_SKILL_LEVEL`] caps the
+//! search (depth and nodes), asks it for several MultiPV lines, and then picks among them
+//! with seeded noise — occasionally swapping in a plausible blunder that loses material
+//! by SEE. Everything random is driven by the engine RNG, so a game is reproducible
+//! given the seed.
+
+use crate::limits::Limits;
+use crate::mv::Move;
+use crate::position::Position;
+use crate::search::RootLine;
+use crate::search::SearchInfo;
+use crate::see::SEE_PAWN;
+use crate::see::see;
+use crate::util::SplitMix64;
+
+/// Full strength: no caps, no noise.
+pub(crate) const MAX_SKILL_LEVEL: u8 = 20;
+
+/// Candidate lines the noisy pick chooses between.
+const SKILL_MULTIPV: u8 = 4;
+
+/// Upper bound on the score spread the noise may bridge — roughly one pawn in PST units.
+const SKILL_NOISE_CAP: i32 = 30;
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Skill {
+    level: u8,
+}
+
+impl Skill {
+    /// `None` at full strength, where the normal search path applies.
+    pub(crate) fn new(level: u8) -> Option<Self> { if level >= MAX_SKILL_LEVEL { None } else { Some(Skill { level }) } }
+
+    /// Tighten `limits` for this level: depth `1 + level / 2`, a node budget doubling
+    /// every two levels from 1000, and at least [`SKILL_MULTIPV`] lines.
+    pub(crate) fn limits(self, mut limits: Limits) -> Limits {
+        let half = self.level / 2;
+        let nodes = 1000u64 << half;
+        limits.max_depth = limits.max_depth.min(1 + half);
+        limits.max_nodes = Some(limits.max_nodes.map_or(nodes, |n| n.min(nodes)));
+        limits.multipv = limits.multipv.max(SKILL_MULTIPV);
+        limits
+    }
+
+    /// Chance in 1/1000 of replacing the pick with a blunder: 25% at level 0, falling
+    /// linearly to 1.25% at level 19.
+    fn blunder_permille(self) -> u64 { (MAX_SKILL_LEVEL - self.level) as u64 * 1000 / 80 }
+
+    /// Roll for a blunder: with [`blunder_permille`](Self::blunder_permille) chance, a
+    /// move from `legal` (the root's legal moves) that loses material by SEE. The caller
+    /// has to score it, since it need not be among the searched lines.
+    pub(crate) fn blunder(self, pos: &Position, legal: &[Move], rng: &mut SplitMix64) -> Option<Move> {
+        if rng.next_u64() % 1000 < self.blunder_permille() { blunder(pos, legal, rng) } else { None }
+    }
+
+    /// Choose the line to play from a finished search. Returns `None` when the search
+    /// found no move.
+    pub(crate) fn pick(self, info: &SearchInfo, rng: &mut SplitMix64) -> Option<RootLine> {
+        let top = info.lines.first().filter(|line| !line.pv.is_empty())?;
+
+        // Stockfish-style weakening: push each line's score up by a share of its gap to the
+        // best line plus a random term, then play the highest pushed score.
+        let weakness = 120 - 2 * self.level as i32;
+        let worst = info.lines.last().map_or(top.score, |line| line.score);
+        let delta = (top.score - worst).min(SKILL_NOISE_CAP);
+        let mut best = top;
+        let mut best_value = i32::MIN;
+        for line in info.lines.iter().filter(|line| !line.pv.is_empty()) {
+            let noise = (rng.next_u64() % weakness as u64) as i32;
+            let push = (weakness * (top.score - line.score) + delta * noise) / 128;
+            if line.score + push > best_value {
+                best_value = line.score + push;
+                best = line;
+            }
+        }
+        Some(best.clone())
+    }
+}
+
+/// A legal move that loses material by SEE, drawn with weight falling off with the square
+/// of the loss so that dropping a pawn is far more likely than dropping a rook.
+fn blunder(pos: &Position, legal: &[Move], rng: &mut SplitMix64) -> Option<Move> {
+    let weighted: Vec<(Move, u64)> = legal
+        .iter()
+        .filter_map(|&mv| {
+            let loss = -see(pos, mv);
+            (loss > 0)
+                .then(|| (mv, (100 * SEE_PAWN * SEE_PAWN / ((loss + SEE_PAWN) * (loss + SEE_PAWN))).max(1) as u64))
+        })
+        .collect();
+    let total: u64 = weighted.iter().map(|&(_, w)| w).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.next_u64() % total;
+    for (mv, w) in weighted {
+        if roll < w {
+            return Some(mv);
+        }
+        roll -= w;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::square::Square;
+
+    fn line(score: i32, iccs: &str) -> RootLine { RootLine { score, pv: vec![Move::from_iccs(iccs).unwrap()] } }
+
+    #[test]
+    fn full_strength_has_no_skill() {
+        assert!(Skill::new(MAX_SKILL_LEVEL).is_none());
+        assert!(Skill::new(0).is_some());
+    }
+
+    #[test]
+    fn limits_are_capped_by_level() {
+        let limits = Skill::new(4).unwrap().limits(Limits::new().depth(20));
+        assert_eq!(limits.max_depth, 3);
+        assert_eq!(limits.max_nodes, Some(4000));
+        assert_eq!(limits.multipv, SKILL_MULTIPV);
+    }
+
+    #[test]
+    fn pick_is_reproducible_and_varies() {
+        let info = SearchInfo {
+            lines: vec![line(20, "h2e2"), line(12, "b2e2"), line(8, "h0g2"), line(-5, "a3a4")],
+            ..Default::default()
+        };
+        let skill = Skill::new(0).unwrap();
+        let picks = |seed| {
+            let mut rng = SplitMix64::new(seed);
+            (0..64).map(|_| skill.pick(&info, &mut rng).unwrap().pv[0]).collect::<Vec<_>>()
+        };
+        let a = picks(7);
+        assert_eq!(a, picks(7));
+        assert!(a.iter().any(|&mv| mv != info.lines[0].pv[0]));
+    }
+
+    #[test]
+    fn blunders_lose_material() {
+        // The red rook on e5 hangs on d5 and f5, both covered by the black pawns above.
+        let pos = Position::from_fen("3k5/9/9/3p1p3/4R4/9/9/9/9/5K3 w").unwrap();
+        let e5 = Square::from_iccs("e5").unwrap();
+        let legal: Vec<Move> = ["e5e6", "e5e4", "e5d5", "e5f5"].iter().map(|s| Move::from_iccs(s).unwrap()).collect();
+        let mut rng = SplitMix64::new(1);
+        for _ in 0..32 {
+            let mv = blunder(&pos, &legal, &mut rng).unwrap();
+            assert_eq!(mv.src(), e5);
+            assert!(see(&pos, mv) < 0);
+        }
+    }
+}
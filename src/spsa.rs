@@ -0,0 +1,282 @@
+//! SPSA tuning of [`SearchParams`] by local self-play.
+//!
+//! Each iteration perturbs every tuned parameter by `±c_k` with a random sign, plays game
+//! pairs between the `θ + c_k·Δ` and `θ − c_k·Δ` engines (same random opening, colours
+//! swapped), and moves `θ` along `Δ` in proportion to the match result. Gains follow the
+//! usual schedule `a_k = a / (A + k)^0.602`, `c_k = c / k^0.101`, with `a` and `c` set so
+//! that the final perturbation is the parameter's `c_end` and the final step a fraction
+//! `learning_rate · c_end²` of it, as in the fishtest implementation.
+//!
+//! ```no_run
+//! use chessai::{Limits, SearchParams, SpsaTuner};
+//!
+//! let mut tuner = SpsaTuner::new(SearchParams::default())
+//!     .tune(&["rfp_margin", "razor_margin", "qsearch_delta"])
+//!     .iterations(500)
+//!     .limits(Limits::new().nodes(2000))
+//!     .threads(8);
+//! let tuned = tuner.run(|iteration, params| eprintln!("{iteration}: {}", params.qsearch_delta));
+//! print!("{tuned}");
+//! ```
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::thread;
+
+use crate::engine::Engine;
+use crate::fen::STARTING_FEN;
+use crate::limits::Limits;
+use crate::mv::Move;
+use crate::search::SCALAR_PARAMS;
+use crate::search::SearchParams;
+use crate::selfplay::play_game;
+use crate::selfplay::random_opening;
+use crate::util::SplitMix64;
+
+const ALPHA: f64 = 0.602;
+const GAMMA: f64 = 0.101;
+
+/// One tuned scalar: its index in [`SCALAR_PARAMS`], current value and final perturbation.
+struct Tuned {
+    index: usize,
+    theta: f64,
+    c_end: f64,
+}
+
+pub struct SpsaTuner {
+    base: SearchParams,
+    tuned: Vec<Tuned>,
+    iterations: u32,
+    pairs: u32,
+    limits: Limits,
+    opening_plies: u8,
+    threads: usize,
+    hash_mb: usize,
+    learning_rate: f64,
+    rng: SplitMix64,
+    iteration: u32,
+}
+
+impl SpsaTuner {
+    /// Start from `params`, tuning every scalar parameter. Each perturbation ends at a
+    /// twentieth of the parameter's allowed range (at least 1).
+    pub fn new(params: SearchParams) -> Self {
+        let tuned = SCALAR_PARAMS
+            .iter()
+            .zip(params.scalars())
+            .enumerate()
+            .map(|(index, (&(_, min, max), value))| Tuned {
+                index,
+                theta: value as f64,
+                c_end: ((max - min) as f64 / 20.0).max(1.0),
+            })
+            .collect();
+        SpsaTuner {
+            base: params,
+            tuned,
+            iterations: 100,
+            pairs: 8,
+            limits: Limits::new().nodes(1000),
+            opening_plies: 6,
+            threads: 1,
+            hash_mb: 4,
+            learning_rate: 0.002,
+            rng: SplitMix64::new(0x5053_5341),
+            iteration: 0,
+        }
+    }
+
+    /// Restrict tuning to the named parameters (the `SearchParams` text-form keys); the
+    /// others keep their starting values.
+    ///
+    /// # Panics
+    ///
+    /// If a name is not a scalar search parameter.
+    #[must_use]
+    pub fn tune(mut self, names: &[&str]) -> Self {
+        for name in names {
+            assert!(SCALAR_PARAMS.iter().any(|(n, _, _)| n == name), "unknown search parameter {name:?}");
+        }
+        self.tuned.retain(|t| names.contains(&SCALAR_PARAMS[t.index].0));
+        self
+    }
+
+    /// Total number of iterations; also fixes the gain schedule.
+    #[must_use]
+    pub fn iterations(mut self, n: u32) -> Self {
+        self.iterations = n.max(1);
+        self
+    }
+
+    /// Game pairs (two games, colours swapped) played per iteration.
+    #[must_use]
+    pub fn pairs_per_iteration(mut self, n: u32) -> Self {
+        self.pairs = n.max(1);
+        self
+    }
+
+    /// Search limits of every move in the self-play games (default: 1000 nodes).
+    #[must_use]
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Random plies played from the starting position before each game pair.
+    #[must_use]
+    pub fn opening_plies(mut self, n: u8) -> Self {
+        self.opening_plies = n;
+        self
+    }
+
+    /// Game pairs played concurrently.
+    #[must_use]
+    pub fn threads(mut self, n: usize) -> Self {
+        self.threads = n.max(1);
+        self
+    }
+
+    /// Hash size of each self-play engine, in megabytes.
+    #[must_use]
+    pub fn hash_size(mut self, mb: usize) -> Self {
+        self.hash_mb = mb;
+        self
+    }
+
+    /// Final step size relative to `c_end²` (fishtest's `r_end`).
+    #[must_use]
+    pub fn learning_rate(mut self, r_end: f64) -> Self {
+        self.learning_rate = r_end;
+        self
+    }
+
+    /// Seed for the openings and perturbation signs.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = SplitMix64::new(seed);
+        self
+    }
+
+    /// The current estimate, rounded to the nearest allowed values.
+    pub fn params(&self) -> SearchParams { self.with_offsets(&vec![0.0; self.tuned.len()]) }
+
+    /// Iterations run so far.
+    pub fn iteration(&self) -> u32 { self.iteration }
+
+    /// Run the remaining iterations. `progress` receives the iteration number and the
+    /// estimate after it. Returns the final estimate.
+    pub fn run(&mut self, mut progress: impl FnMut(u32, &SearchParams)) -> SearchParams {
+        while self.iteration < self.iterations {
+            self.step();
+            progress(self.iteration, &self.params());
+        }
+        self.params()
+    }
+
+    /// One iteration. Returns the perturbed-up engine's score minus the perturbed-down
+    /// engine's, in points over all games played (`-2·pairs..=2·pairs`).
+    pub fn step(&mut self) -> f64 {
+        let n = self.iterations as f64;
+        let k = self.iteration as f64 + 1.0;
+        let big_a = 0.1 * n;
+        let c_scale = (n / k).powf(GAMMA);
+        let a_scale = ((big_a + n) / (big_a + k)).powf(ALPHA);
+
+        let signs: Vec<f64> =
+            self.tuned.iter().map(|_| if self.rng.next_u64() & 1 == 0 { 1.0 } else { -1.0 }).collect();
+        let c_k: Vec<f64> = self.tuned.iter().map(|t| t.c_end * c_scale).collect();
+        let plus = self.with_offsets(&signs.iter().zip(&c_k).map(|(s, c)| s * c).collect::<Vec<_>>());
+        let minus = self.with_offsets(&signs.iter().zip(&c_k).map(|(s, c)| -s * c).collect::<Vec<_>>());
+        let openings: Vec<Vec<Move>> =
+            (0..self.pairs).map(|_| random_opening(&mut self.rng, self.opening_plies)).collect();
+
+        let result = self.play_pairs(plus, minus, &openings);
+        for ((t, sign), c) in self.tuned.iter_mut().zip(&signs).zip(&c_k) {
+            // a_k / c_k² · c_k · result · Δ, with a_end = r_end · c_end².
+            let a_k = self.learning_rate * t.c_end * t.c_end * a_scale;
+            let (_, min, max) = SCALAR_PARAMS[t.index];
+            t.theta = (t.theta + a_k / c * result * sign).clamp(min as f64, max as f64);
+        }
+        self.iteration += 1;
+        result
+    }
+
+    /// The base parameters with every tuned scalar set to `θ + offset`, rounded and clamped.
+    fn with_offsets(&self, offsets: &[f64]) -> SearchParams {
+        let mut params = self.base.clone();
+        let slots = params.scalars_mut();
+        for (t, offset) in self.tuned.iter().zip(offsets) {
+            let (_, min, max) = SCALAR_PARAMS[t.index];
+            *slots[t.index] = ((t.theta + offset).round() as i32).clamp(min, max);
+        }
+        params
+    }
+
+    /// Play one game pair per opening, `threads` pairs at a time. Returns the `plus`
+    /// engine's points minus the `minus` engine's.
+    fn play_pairs(&self, plus: SearchParams, minus: SearchParams, openings: &[Vec<Move>]) -> f64 {
+        let limits = self.limits;
+        let (plus, minus) = (Arc::new(plus), Arc::new(minus));
+        let next = AtomicUsize::new(0);
+        let total = Mutex::new(0.0);
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(openings.len()) {
+                scope.spawn(|| {
+                    let engine = |params: &SearchParams| {
+                        Engine::builder().use_book(false).hash_size(self.hash_mb).search_params(params.clone()).build()
+                    };
+                    let (mut up, mut down) = (engine(&plus), engine(&minus));
+                    let mut points = 0.0;
+                    while let Some(opening) = openings.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        let play = |red: &mut Engine, black: &mut Engine| {
+                            let status = play_game(red, black, STARTING_FEN, opening, limits, |_, _, _| {});
+                            status.expect("startpos FEN parses").red_score().unwrap_or(0.5)
+                        };
+                        let first = play(&mut up, &mut down);
+                        let second = play(&mut down, &mut up);
+                        // Points for `up` minus points for `down` over both games.
+                        points += (2.0 * first - 1.0) - (2.0 * second - 1.0);
+                    }
+                    *total.lock().unwrap() += points;
+                });
+            }
+        });
+        total.into_inner().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tunes_within_bounds_reproducibly() {
+        let run = || {
+            let mut tuner = SpsaTuner::new(SearchParams::default())
+                .tune(&["rfp_margin", "qsearch_delta"])
+                .iterations(2)
+                .pairs_per_iteration(1)
+                .limits(Limits::new().depth(1))
+                .threads(2)
+                .hash_size(1)
+                .learning_rate(0.05)
+                .seed(3);
+            let mut seen = Vec::new();
+            let params = tuner.run(|iteration, params| seen.push((iteration, params.clone())));
+            assert_eq!(seen.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [1, 2]);
+            params
+        };
+        let params = run();
+        assert_eq!(params, run());
+        let defaults = SearchParams::default();
+        assert_eq!(
+            SearchParams { rfp_margin: defaults.rfp_margin, qsearch_delta: defaults.qsearch_delta, ..params.clone() },
+            defaults
+        );
+        for (&(_, min, max), value) in SCALAR_PARAMS.iter().zip(params.scalars()) {
+            assert!((min..=max).contains(&value));
+        }
+    }
+}
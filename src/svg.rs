@@ -0,0 +1,349 @@
+//! SVG diagrams of a [`Position`]: the grid with river and palace diagonals, the pieces,
+//! and optional square highlights and move arrows.
+//!
+//! ```
+//! use chessai::{Move, Position, STARTING_FEN, SvgStyle};
+//!
+//! let pos = Position::from_fen(STARTING_FEN).unwrap();
+//! let svg = pos.to_svg(&SvgStyle::new().size(450).arrow(Move::from_iccs("h2e2").unwrap()));
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+use std::fmt::Write;
+
+use crate::color::Color;
+use crate::mv::Move;
+use crate::position::Position;
+use crate::render::Glyphs;
+use crate::render::chinese_glyph;
+use crate::square::Square;
+
+/// Colors of a diagram, as SVG paint values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SvgTheme {
+    pub background: String,
+    pub lines: String,
+    pub piece_fill: String,
+    pub red: String,
+    pub black: String,
+    pub highlight: String,
+    pub arrow: String,
+}
+
+impl Default for SvgTheme {
+    fn default() -> Self { Self::classic() }
+}
+
+impl SvgTheme {
+    /// Wood board, black lines.
+    pub fn classic() -> Self {
+        SvgTheme {
+            background: "#f2d7a0".to_string(),
+            lines: "#5a3a1a".to_string(),
+            piece_fill: "#fbf1dc".to_string(),
+            red: "#c0281e".to_string(),
+            black: "#1e1e1e".to_string(),
+            highlight: "#4caf50".to_string(),
+            arrow: "#1f6fd1".to_string(),
+        }
+    }
+
+    /// Black and white, for print.
+    pub fn print() -> Self {
+        SvgTheme {
+            background: "#ffffff".to_string(),
+            lines: "#000000".to_string(),
+            piece_fill: "#ffffff".to_string(),
+            red: "#000000".to_string(),
+            black: "#000000".to_string(),
+            highlight: "#9e9e9e".to_string(),
+            arrow: "#555555".to_string(),
+        }
+    }
+}
+
+/// Options of [`Position::to_svg`].
+#[derive(Clone, Debug)]
+pub struct SvgStyle {
+    size: u32,
+    theme: SvgTheme,
+    glyphs: Glyphs,
+    orientation: Color,
+    coordinates: bool,
+    highlights: Vec<Square>,
+    arrows: Vec<Move>,
+    pv: Vec<Move>,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self { Self::new() }
+}
+
+impl SvgStyle {
+    /// 400 pixels wide, classic theme, Chinese glyphs with coordinates, seen from red's side.
+    pub fn new() -> Self {
+        SvgStyle {
+            size: 400,
+            theme: SvgTheme::classic(),
+            glyphs: Glyphs::Chinese,
+            orientation: Color::Red,
+            coordinates: true,
+            highlights: Vec::new(),
+            arrows: Vec::new(),
+            pv: Vec::new(),
+        }
+    }
+
+    /// Width in pixels; the height is 11/10 of it.
+    #[must_use]
+    pub fn size(mut self, px: u32) -> Self {
+        self.size = px.max(100);
+        self
+    }
+
+    #[must_use]
+    pub fn theme(mut self, theme: SvgTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Piece labels. Chinese glyphs need a CJK font on the viewing side.
+    #[must_use]
+    pub fn glyphs(mut self, glyphs: Glyphs) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// The side sitting at the bottom of the diagram.
+    #[must_use]
+    pub fn orientation(mut self, color: Color) -> Self {
+        self.orientation = color;
+        self
+    }
+
+    /// File letters and rank digits in the margin.
+    #[must_use]
+    pub fn coordinates(mut self, yes: bool) -> Self {
+        self.coordinates = yes;
+        self
+    }
+
+    /// Mark a square; may be called repeatedly.
+    #[must_use]
+    pub fn highlight(mut self, sq: Square) -> Self {
+        self.highlights.push(sq);
+        self
+    }
+
+    /// Draw an arrow for `mv`; may be called repeatedly.
+    #[must_use]
+    pub fn arrow(mut self, mv: Move) -> Self {
+        self.arrows.push(mv);
+        self
+    }
+
+    /// Draw a principal variation as numbered arrows, fading with depth.
+    #[must_use]
+    pub fn pv(mut self, pv: &[Move]) -> Self {
+        self.pv = pv.to_vec();
+        self
+    }
+}
+
+impl Position {
+    /// A self-contained SVG document of the position.
+    pub fn to_svg(&self, style: &SvgStyle) -> String {
+        let cell = style.size as f64 / 10.0;
+        let (width, height) = (cell * 10.0, cell * 11.0);
+        let flipped = style.orientation == Color::Black;
+        let point = |sq: Square| {
+            let (col, row) = if flipped { (8 - sq.file(), sq.rank()) } else { (sq.file(), 9 - sq.rank()) };
+            (cell * (1.0 + col as f64), cell * (1.0 + row as f64))
+        };
+        let grid = |col: u8, row: u8| (cell * (1.0 + col as f64), cell * (1.0 + row as f64));
+        let theme = &style.theme;
+
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.0}" height="{height:.0}" viewBox="0 0 {width:.1} {height:.1}">"#
+        );
+        let _ = write!(
+            svg,
+            r#"<defs><marker id="arrowhead" viewBox="0 0 10 10" refX="5" refY="5" markerWidth="3" markerHeight="3" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="{}"/></marker></defs>"#,
+            theme.arrow
+        );
+        let _ = write!(svg, r#"<rect width="100%" height="100%" fill="{}"/>"#, theme.background);
+
+        // Grid: ten ranks, the edge files across the river, inner files stopping at it.
+        let line = |svg: &mut String, (x1, y1): (f64, f64), (x2, y2): (f64, f64), w: f64| {
+            let _ = write!(
+                svg,
+                r#"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}" stroke="{}" stroke-width="{w:.2}"/>"#,
+                theme.lines
+            );
+        };
+        let thin = cell / 40.0;
+        for row in 0..10 {
+            line(&mut svg, grid(0, row), grid(8, row), thin);
+        }
+        for col in 0..9 {
+            if col == 0 || col == 8 {
+                line(&mut svg, grid(col, 0), grid(col, 9), thin);
+            } else {
+                line(&mut svg, grid(col, 0), grid(col, 4), thin);
+                line(&mut svg, grid(col, 5), grid(col, 9), thin);
+            }
+        }
+        for (top, bottom) in [(0, 2), (7, 9)] {
+            line(&mut svg, grid(3, top), grid(5, bottom), thin);
+            line(&mut svg, grid(5, top), grid(3, bottom), thin);
+        }
+        let (x0, y0) = grid(0, 0);
+        let _ = write!(
+            svg,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="none" stroke="{}" stroke-width="{:.2}"/>"#,
+            x0 - cell / 8.0,
+            y0 - cell / 8.0,
+            cell * 8.25,
+            cell * 9.25,
+            theme.lines,
+            thin * 3.0
+        );
+        let (_, river) = grid(0, 4);
+        for (x, text) in [(cell * 3.0, "楚 河"), (cell * 7.0, "漢 界")] {
+            let _ = write!(
+                svg,
+                r#"<text x="{x:.1}" y="{:.1}" font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central" font-family="serif">{text}</text>"#,
+                river + cell / 2.0,
+                cell * 0.45,
+                theme.lines
+            );
+        }
+
+        if style.coordinates {
+            for file in 0..9 {
+                let (x, _) = point(Square::from_rank_file(0, file).expect("on board"));
+                let _ = write!(
+                    svg,
+                    r#"<text x="{x:.1}" y="{:.1}" font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central" font-family="sans-serif">{}</text>"#,
+                    cell * 10.5,
+                    cell * 0.3,
+                    theme.lines,
+                    (b'a' + file) as char
+                );
+            }
+            for rank in 0..10 {
+                let (_, y) = point(Square::from_rank_file(rank, 0).expect("on board"));
+                let _ = write!(
+                    svg,
+                    r#"<text x="{:.1}" y="{y:.1}" font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central" font-family="sans-serif">{rank}</text>"#,
+                    cell * 0.4,
+                    cell * 0.3,
+                    theme.lines
+                );
+            }
+        }
+
+        for &sq in &style.highlights {
+            let (x, y) = point(sq);
+            let _ = write!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" fill-opacity="0.45"/>"#,
+                x - cell / 2.0,
+                y - cell / 2.0,
+                cell,
+                cell,
+                theme.highlight
+            );
+        }
+
+        for rank in 0..10 {
+            for file in 0..9 {
+                let sq = Square::from_rank_file(rank, file).expect("on board");
+                let Some(piece) = self.piece_at(sq) else { continue };
+                let (x, y) = point(sq);
+                let ink = if piece.color() == Color::Red { &theme.red } else { &theme.black };
+                let label = match style.glyphs {
+                    Glyphs::Ascii => piece.fen_char(),
+                    Glyphs::Chinese => chinese_glyph(piece),
+                };
+                let _ = write!(
+                    svg,
+                    r#"<circle cx="{x:.1}" cy="{y:.1}" r="{:.1}" fill="{}" stroke="{ink}" stroke-width="{:.2}"/>"#,
+                    cell * 0.44,
+                    theme.piece_fill,
+                    thin * 2.0
+                );
+                let _ = write!(
+                    svg,
+                    r#"<text x="{x:.1}" y="{y:.1}" font-size="{:.1}" fill="{ink}" text-anchor="middle" dominant-baseline="central" font-family="serif">{label}</text>"#,
+                    cell * 0.55
+                );
+            }
+        }
+
+        // Arrows stop short of the destination centre so the head lands on the piece edge.
+        let arrow = |svg: &mut String, mv: Move, opacity: f64, number: Option<usize>| {
+            let ((x1, y1), (x2, y2)) = (point(mv.src()), point(mv.dst()));
+            let len = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(1.0);
+            let shorten = cell * 0.3 / len;
+            let (ex, ey) = (x2 - (x2 - x1) * shorten, y2 - (y2 - y1) * shorten);
+            let _ = write!(
+                svg,
+                r#"<line x1="{x1:.1}" y1="{y1:.1}" x2="{ex:.1}" y2="{ey:.1}" stroke="{}" stroke-width="{:.2}" stroke-opacity="{opacity:.2}" stroke-linecap="round" marker-end="url(#arrowhead)"/>"#,
+                theme.arrow,
+                cell * 0.12
+            );
+            if let Some(n) = number {
+                let _ = write!(
+                    svg,
+                    r#"<text x="{:.1}" y="{:.1}" font-size="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central" font-family="sans-serif" font-weight="bold">{n}</text>"#,
+                    (x1 + x2) / 2.0,
+                    (y1 + y2) / 2.0,
+                    cell * 0.3,
+                    theme.arrow
+                );
+            }
+        };
+        for &mv in &style.arrows {
+            arrow(&mut svg, mv, 0.8, None);
+        }
+        for (i, &mv) in style.pv.iter().enumerate() {
+            arrow(&mut svg, mv, (0.85 - 0.1 * i as f64).max(0.3), Some(i + 1));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::STARTING_FEN;
+
+    #[test]
+    fn draws_board_pieces_and_annotations() {
+        let pos = Position::from_fen(STARTING_FEN).unwrap();
+        let mv = Move::from_iccs("h2e2").unwrap();
+        let svg = pos.to_svg(&SvgStyle::new().size(500).highlight(mv.src()).arrow(mv));
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="500" height="550""#));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<circle").count(), 32);
+        assert_eq!(svg.matches("<line").count(), 10 + 2 + 7 * 2 + 4 + 1);
+        assert!(svg.contains("帥") && svg.contains("將") && svg.contains("楚 河"));
+        assert!(svg.contains(r##"fill="#4caf50""##));
+
+        // Red ink sits bottom centre (the king) from red's side and top centre from black's.
+        let red_at = |y: &str| format!(r##"<text x="250.0" y="{y}" font-size="27.5" fill="#c0281e""##);
+        assert!(svg.contains(&red_at("500.0")) && !svg.contains(&red_at("50.0")));
+        let flipped = pos.to_svg(&SvgStyle::new().size(500).orientation(Color::Black).glyphs(Glyphs::Ascii));
+        assert!(flipped.contains(&red_at("50.0")) && !flipped.contains(&red_at("500.0")));
+        assert!(flipped.contains(">K</text>") && !flipped.contains("帥"));
+
+        let pv = [mv, Move::from_iccs("h9g7").unwrap(), Move::from_iccs("h0g2").unwrap()];
+        let with_pv = pos.to_svg(&SvgStyle::new().theme(SvgTheme::print()).pv(&pv).coordinates(false));
+        assert_eq!(with_pv.matches("marker-end").count(), 3);
+        assert!(with_pv.contains(">3</text>") && !with_pv.contains(">i</text>"));
+    }
+}
@@ -0,0 +1,728 @@
+//! Endgame tablebases for small material signatures, built by retrograde analysis.
+//!
+//! A signature such as `KRKAA` lists the pieces of the side that is tabulated as red
+//! (here a rook), then those of the side tabulated as black (two advisors). Positions with
+//! the colours swapped are probed through the same table by flipping the board across
+//! the river, and the file mirror halves the table: the first side's king is always
+//! indexed on files `d`/`e`.
+//!
+//! Generation gathers every position's legal moves once, seeds the mates (no legal move
+//! loses — stalemate included) and the results of captures from the smaller tables, then
+//! resolves positions in order of increasing distance to mate. Whatever is left over is
+//! a draw; repetitions are scored as draws, without the perpetual-check rules.
+//!
+//! ```no_run
+//! use chessai::{Engine, Limits, Tablebases};
+//!
+//! let mut tb = Tablebases::new();
+//! tb.generate("KRKAA")?; // also builds KRKA, KRK, KKAA, ...
+//! tb.save_dir("tablebases")?;
+//! let mut engine = Engine::builder().tablebases(tb).build();
+//! engine.set_fen("3ak4/4a4/9/9/9/9/9/9/9/3KR4 w")?;
+//! let info = engine.search(Limits::new().depth(1)); // plays the fastest mate
+//! # let _ = info;
+//! # Ok::<(), chessai::ChessAIError>(())
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::color::Color;
+use crate::error::ChessAIError;
+use crate::movegen::MoveList;
+use crate::movegen::generate_legal;
+use crate::mv::Move;
+use crate::piece::Piece;
+use crate::piece::PieceType;
+use crate::position::Position;
+use crate::square::Square;
+use crate::util::Fnv64;
+
+/// Largest table accepted for generation or loading, in positions.
+const MAX_TABLE_POSITIONS: usize = 1 << 27;
+
+/// Leading bytes of a table file.
+const TABLE_FILE_MAGIC: [u8; 8] = *b"CHESSAIB";
+/// Version of the table file layout.
+const TABLE_FILE_VERSION: u32 = 1;
+/// File extension of saved tables.
+const TABLE_FILE_EXT: &str = "xtb";
+
+/// Stored value of a drawn position.
+const DRAW: u16 = 0;
+/// Stored value of an index that is no legal position.
+const INVALID: u16 = u16::MAX;
+/// Working value during generation.
+const UNRESOLVED: u16 = u16::MAX - 1;
+
+/// Order in which non-king pieces appear in signatures and in the index.
+const PIECE_ORDER: [PieceType; 6] =
+    [PieceType::Rook, PieceType::Knight, PieceType::Cannon, PieceType::Pawn, PieceType::Advisor, PieceType::Bishop];
+
+/// Most pieces of one kind a side can have.
+const fn max_count(kind: PieceType) -> u8 {
+    match kind {
+        PieceType::King => 1,
+        PieceType::Pawn => 5,
+        _ => 2,
+    }
+}
+
+/// Game-theoretic value for the side to move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Wdl {
+    Win,
+    Draw,
+    Loss,
+}
+
+/// Result of a tablebase probe: the value for the side to move and, unless drawn, the
+/// number of plies to mate with best play.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TbProbe {
+    pub wdl: Wdl,
+    pub dtm: u16,
+}
+
+impl TbProbe {
+    fn decode(value: u16) -> Option<TbProbe> {
+        match value {
+            INVALID | UNRESOLVED => None,
+            DRAW => Some(TbProbe { wdl: Wdl::Draw, dtm: 0 }),
+            v => {
+                let dtm = v - 1;
+                Some(TbProbe { wdl: if dtm % 2 == 1 { Wdl::Win } else { Wdl::Loss }, dtm })
+            }
+        }
+    }
+}
+
+/// Non-king piece counts of the side tabulated as red and of the side tabulated as black.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+struct Signature {
+    counts: [[u8; PieceType::COUNT]; 2],
+}
+
+impl Signature {
+    fn of(pos: &Position) -> Signature {
+        let mut counts = [[0; PieceType::COUNT]; 2];
+        for color in Color::ALL {
+            for kind in PIECE_ORDER {
+                counts[color.index()][kind.index()] = pos.pieces(color, kind).popcount() as u8;
+            }
+        }
+        Signature { counts }
+    }
+
+    fn flipped(self) -> Signature { Signature { counts: [self.counts[1], self.counts[0]] } }
+
+    fn pieces(self) -> usize { self.counts.iter().flatten().map(|&n| n as usize).sum() }
+
+    /// Signatures reachable by one capture.
+    fn captures(self) -> impl Iterator<Item = Signature> {
+        Color::ALL.into_iter().flat_map(move |color| {
+            PIECE_ORDER.into_iter().filter(move |kind| self.counts[color.index()][kind.index()] > 0).map(move |kind| {
+                let mut sub = self;
+                sub.counts[color.index()][kind.index()] -= 1;
+                sub
+            })
+        })
+    }
+}
+
+impl FromStr for Signature {
+    type Err = ChessAIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || ChessAIError::BadTablebase(format!("bad material signature {s:?}; expected e.g. KRKAA"));
+        let mut counts = [[0u8; PieceType::COUNT]; 2];
+        let mut side = None;
+        for c in s.chars() {
+            match PieceType::from_fen_char(c).ok_or_else(bad)? {
+                PieceType::King => {
+                    side = match side {
+                        None => Some(0),
+                        Some(0) => Some(1),
+                        Some(_) => return Err(bad()),
+                    }
+                }
+                kind => {
+                    let count = &mut counts[side.ok_or_else(bad)?][kind.index()];
+                    *count += 1;
+                    if *count > max_count(kind) {
+                        return Err(bad());
+                    }
+                }
+            }
+        }
+        if side != Some(1) {
+            return Err(bad());
+        }
+        Ok(Signature { counts })
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for side in &self.counts {
+            f.write_str("K")?;
+            for kind in PIECE_ORDER {
+                for _ in 0..side[kind.index()] {
+                    write!(f, "{}", kind.fen_char())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Squares a piece can ever stand on. The red king's domain is cut to files `d`/`e`.
+fn domain(color: Color, kind: PieceType, canonical_king: bool) -> Vec<Square> {
+    (0..Square::COUNT as u8)
+        .map(Square::new_unchecked)
+        .filter(|&sq| {
+            // Work in red's frame: rank 0 is the own back rank.
+            let own = if color == Color::Red { sq } else { sq.flip_rank() };
+            let (rank, file) = (own.rank(), own.file());
+            match kind {
+                PieceType::King => own.is_in_palace(Color::Red) && !(canonical_king && file > 4),
+                PieceType::Advisor => own.is_in_palace(Color::Red) && rank.abs_diff(1) == file.abs_diff(4),
+                PieceType::Bishop => rank <= 4 && rank % 2 == 0 && file % 2 == 0 && (rank / 2 + file / 2) % 2 == 1,
+                PieceType::Pawn => rank >= 5 || (rank >= 3 && file % 2 == 0),
+                _ => true,
+            }
+        })
+        .collect()
+}
+
+/// One piece's coordinate in the index.
+struct Slot {
+    piece: Piece,
+    squares: Vec<Square>,
+    /// Square → position in `squares`, `u8::MAX` where the piece cannot stand.
+    lookup: [u8; Square::COUNT],
+    /// Same piece as the previous slot; such runs are indexed in ascending square order.
+    repeats: bool,
+}
+
+/// The table of one signature, in its own frame (first side red).
+struct Table {
+    slots: Vec<Slot>,
+    values: Vec<u16>,
+}
+
+impl Table {
+    fn layout(sig: Signature) -> Vec<Slot> {
+        let mut pieces = vec![Piece::new(Color::Red, PieceType::King), Piece::new(Color::Black, PieceType::King)];
+        for color in Color::ALL {
+            for kind in PIECE_ORDER {
+                for _ in 0..sig.counts[color.index()][kind.index()] {
+                    pieces.push(Piece::new(color, kind));
+                }
+            }
+        }
+        let mut slots: Vec<Slot> = Vec::with_capacity(pieces.len());
+        for (i, &piece) in pieces.iter().enumerate() {
+            let squares = domain(piece.color(), piece.kind(), i == 0);
+            let mut lookup = [u8::MAX; Square::COUNT];
+            for (n, sq) in squares.iter().enumerate() {
+                lookup[sq.raw() as usize] = n as u8;
+            }
+            let repeats = i > 0 && pieces[i - 1] == piece;
+            slots.push(Slot { piece, squares, lookup, repeats });
+        }
+        slots
+    }
+
+    fn size(slots: &[Slot]) -> Option<usize> {
+        slots.iter().try_fold(2usize, |acc, slot| acc.checked_mul(slot.squares.len()))
+    }
+
+    /// Index of `pos`, read in the table frame: with `flip`, black's pieces play the red
+    /// slots (and vice versa) on a board turned across the river.
+    fn index_of(&self, pos: &Position, flip: bool) -> Option<usize> {
+        let mut squares = [Square::new_unchecked(0); 32];
+        let mut n = 0;
+        let mut i = 0;
+        while i < self.slots.len() {
+            let piece = self.slots[i].piece;
+            let color = if flip { piece.color().flip() } else { piece.color() };
+            for sq in pos.pieces(color, piece.kind()).iter() {
+                squares[n] = if flip { sq.flip_rank() } else { sq };
+                n += 1;
+            }
+            i += 1;
+            while i < self.slots.len() && self.slots[i].repeats {
+                i += 1;
+            }
+        }
+        if n != self.slots.len() {
+            return None;
+        }
+        let squares = &mut squares[..n];
+        if squares[0].file() > 4 {
+            for sq in squares.iter_mut() {
+                *sq = sq.mirror_file();
+            }
+        }
+        let mut start = 0;
+        for end in 1..=n {
+            if end == n || !self.slots[end].repeats {
+                squares[start..end].sort_unstable();
+                start = end;
+            }
+        }
+
+        let stm = if flip { pos.side_to_move().flip() } else { pos.side_to_move() };
+        let mut idx = 0usize;
+        for (slot, sq) in self.slots.iter().zip(squares.iter()).rev() {
+            let coord = slot.lookup[sq.raw() as usize];
+            if coord == u8::MAX {
+                return None;
+            }
+            idx = idx * slot.squares.len() + coord as usize;
+        }
+        Some(idx * 2 + stm.index())
+    }
+
+    /// The position at `idx`, or `None` if the index is no legal position (overlapping
+    /// pieces, a non-canonical order of equal pieces, or the side not to move in check).
+    fn decode(&self, mut idx: usize) -> Option<Position> {
+        let stm = Color::from_index(idx % 2);
+        idx /= 2;
+        let mut pos = Position::empty();
+        let mut prev: Option<Square> = None;
+        for slot in &self.slots {
+            let sq = slot.squares[idx % slot.squares.len()];
+            idx /= slot.squares.len();
+            if pos.piece_at(sq).is_some() || (slot.repeats && prev.is_some_and(|p| p >= sq)) {
+                return None;
+            }
+            pos.put(sq, slot.piece);
+            prev = Some(sq);
+        }
+        pos.set_side_to_move(stm);
+        if pos.is_in_check(stm.flip()) { None } else { Some(pos) }
+    }
+
+    fn probe(&self, pos: &Position, flip: bool) -> Option<TbProbe> {
+        TbProbe::decode(self.values[self.index_of(pos, flip)?])
+    }
+
+    /// Retrograde analysis; every signature reachable by a capture must already be in `tbs`.
+    fn generate(sig: Signature, tbs: &Tablebases) -> Table {
+        let slots = Self::layout(sig);
+        let size = Self::size(&slots).expect("size checked by the caller");
+        let mut table = Table { slots, values: vec![INVALID; size] };
+
+        // Unresolved in-table successors per position; `NEVER` once a capture shows the
+        // side to move can avoid losing.
+        const NEVER: u16 = u16::MAX;
+        let mut remaining = vec![0u16; size];
+        let mut longest = vec![0u16; size];
+        let mut edges: Vec<(u32, u32)> = Vec::new();
+        let mut buckets: Vec<Vec<u32>> = Vec::new();
+        fn push(buckets: &mut Vec<Vec<u32>>, dtm: u16, idx: usize) {
+            if buckets.len() <= dtm as usize {
+                buckets.resize_with(dtm as usize + 1, Vec::new);
+            }
+            buckets[dtm as usize].push(idx as u32);
+        }
+
+        let mut legal = MoveList::new();
+        for idx in 0..size {
+            let Some(mut pos) = table.decode(idx) else { continue };
+            table.values[idx] = UNRESOLVED;
+            legal.clear();
+            generate_legal(&pos, &mut legal);
+            if legal.len() == 0 {
+                push(&mut buckets, 0, idx);
+                continue;
+            }
+            for &mv in legal.iter() {
+                let capture = pos.piece_at(mv.dst()).is_some();
+                let undo = pos.make_move(mv);
+                if capture {
+                    match tbs.probe(&pos).expect("capture tables are generated first") {
+                        TbProbe { wdl: Wdl::Loss, dtm } => {
+                            push(&mut buckets, dtm + 1, idx);
+                            remaining[idx] = NEVER;
+                        }
+                        TbProbe { wdl: Wdl::Win, dtm } => longest[idx] = longest[idx].max(dtm),
+                        TbProbe { wdl: Wdl::Draw, .. } => remaining[idx] = NEVER,
+                    }
+                } else {
+                    let succ = table.index_of(&pos, false).expect("successor stays in the table");
+                    edges.push((succ as u32, idx as u32));
+                    if remaining[idx] != NEVER {
+                        remaining[idx] += 1;
+                    }
+                }
+                pos.undo_move(mv, undo);
+            }
+            if remaining[idx] == 0 {
+                // Every move captures into a lost ending.
+                push(&mut buckets, longest[idx] + 1, idx);
+            }
+        }
+
+        // Predecessor lists in CSR form.
+        let mut start = vec![0u32; size + 1];
+        for &(succ, _) in &edges {
+            start[succ as usize + 1] += 1;
+        }
+        for i in 0..size {
+            start[i + 1] += start[i];
+        }
+        let mut preds = vec![0u32; edges.len()];
+        let mut fill = start.clone();
+        for &(succ, pred) in &edges {
+            preds[fill[succ as usize] as usize] = pred;
+            fill[succ as usize] += 1;
+        }
+        drop(edges);
+
+        // Odd distances are wins for the side to move, even ones losses.
+        let mut dtm = 0;
+        while dtm < buckets.len() {
+            let bucket = std::mem::take(&mut buckets[dtm]);
+            for idx in bucket {
+                let idx = idx as usize;
+                if table.values[idx] != UNRESOLVED {
+                    continue;
+                }
+                table.values[idx] = dtm as u16 + 1;
+                for &pred in &preds[start[idx] as usize..start[idx + 1] as usize] {
+                    let pred = pred as usize;
+                    if table.values[pred] != UNRESOLVED {
+                        continue;
+                    }
+                    if dtm % 2 == 0 {
+                        push(&mut buckets, dtm as u16 + 1, pred);
+                    } else if remaining[pred] != NEVER {
+                        remaining[pred] -= 1;
+                        longest[pred] = longest[pred].max(dtm as u16);
+                        if remaining[pred] == 0 {
+                            push(&mut buckets, longest[pred] + 1, pred);
+                        }
+                    }
+                }
+            }
+            dtm += 1;
+        }
+        for value in table.values.iter_mut().filter(|v| **v == UNRESOLVED) {
+            *value = DRAW;
+        }
+        table
+    }
+
+    /// Layout, integers little-endian: magic, version `u32`, signature length `u8` and
+    /// text, position count `u64`, DTM width `u8` (1 or 2 bytes), the WDL of every
+    /// position at 2 bits each (0 draw, 1 win, 2 loss, 3 no position), every position's
+    /// DTM, and an FNV-1a checksum of everything before it.
+    fn write_to<W: Write>(&self, sig: Signature, mut w: W) -> Result<(), ChessAIError> {
+        let name = sig.to_string();
+        let width: u8 = if self.values.iter().all(|&v| v == INVALID || v <= 256) { 1 } else { 2 };
+        let mut out = Vec::with_capacity(32 + self.values.len() * (width as usize + 1));
+        out.extend_from_slice(&TABLE_FILE_MAGIC);
+        out.extend_from_slice(&TABLE_FILE_VERSION.to_le_bytes());
+        out.push(name.len() as u8);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&(self.values.len() as u64).to_le_bytes());
+        out.push(width);
+        for chunk in self.values.chunks(4) {
+            let mut byte = 0u8;
+            for (i, &v) in chunk.iter().enumerate() {
+                let wdl = match TbProbe::decode(v) {
+                    None => 3,
+                    Some(TbProbe { wdl: Wdl::Draw, .. }) => 0,
+                    Some(TbProbe { wdl: Wdl::Win, .. }) => 1,
+                    Some(TbProbe { wdl: Wdl::Loss, .. }) => 2,
+                };
+                byte |= wdl << (2 * i);
+            }
+            out.push(byte);
+        }
+        for &v in &self.values {
+            let dtm = TbProbe::decode(v).map_or(0, |p| p.dtm);
+            out.extend_from_slice(&dtm.to_le_bytes()[..width as usize]);
+        }
+        let mut sum = Fnv64::new();
+        sum.update(&out);
+        out.extend_from_slice(&sum.finish().to_le_bytes());
+        w.write_all(&out)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    fn read_from<R: Read>(mut r: R) -> Result<(Signature, Table), ChessAIError> {
+        let bad = |what: &str| ChessAIError::BadTablebase(what.to_string());
+        let mut bytes = Vec::new();
+        r.read_to_end(&mut bytes)?;
+        if bytes.len() < 8 {
+            return Err(bad("truncated"));
+        }
+        let (body, checksum) = bytes.split_at(bytes.len() - 8);
+        let mut sum = Fnv64::new();
+        sum.update(body);
+        if sum.finish().to_le_bytes() != checksum {
+            return Err(bad("checksum mismatch"));
+        }
+
+        let mut at = 0;
+        let mut take = |n: usize| -> Result<&[u8], ChessAIError> {
+            let field = body.get(at..at + n).ok_or_else(|| bad("truncated"))?;
+            at += n;
+            Ok(field)
+        };
+        if take(8)? != TABLE_FILE_MAGIC {
+            return Err(bad("not a tablebase file"));
+        }
+        if take(4)? != TABLE_FILE_VERSION.to_le_bytes() {
+            return Err(bad("unsupported version"));
+        }
+        let name_len = take(1)?[0] as usize;
+        let name = std::str::from_utf8(take(name_len)?).map_err(|_| bad("bad signature"))?;
+        let sig: Signature = name.parse()?;
+        let slots = Self::layout(sig);
+        let size = Self::size(&slots).filter(|&n| n <= MAX_TABLE_POSITIONS).ok_or_else(|| bad("table too large"))?;
+        if take(8)? != (size as u64).to_le_bytes() {
+            return Err(bad("position count does not match the signature"));
+        }
+        let width = take(1)?[0] as usize;
+        if width != 1 && width != 2 {
+            return Err(bad("bad DTM width"));
+        }
+        let wdl = take(size.div_ceil(4))?.to_vec();
+        let dtms = take(size * width)?;
+        let mut values = Vec::with_capacity(size);
+        for i in 0..size {
+            let mut raw = [0u8; 2];
+            raw[..width].copy_from_slice(&dtms[i * width..(i + 1) * width]);
+            let dtm = u16::from_le_bytes(raw);
+            values.push(match (wdl[i / 4] >> (2 * (i % 4))) & 0b11 {
+                0 => DRAW,
+                3 => INVALID,
+                kind => {
+                    if (kind == 1) != (dtm % 2 == 1) || dtm >= UNRESOLVED - 1 {
+                        return Err(bad("inconsistent WDL and DTM"));
+                    }
+                    dtm + 1
+                }
+            });
+        }
+        Ok((sig, Table { slots, values }))
+    }
+}
+
+/// A set of endgame tables, probed by material signature.
+#[derive(Default)]
+pub struct Tablebases {
+    tables: HashMap<Signature, Table>,
+    max_pieces: u32,
+}
+
+impl Tablebases {
+    pub fn new() -> Self { Self::default() }
+
+    /// Generate the table for `signature` (e.g. `"KRKAA"`, `"KNPKBB"`) along with every
+    /// table reachable from it by captures. Tables already present are reused.
+    pub fn generate(&mut self, signature: &str) -> Result<(), ChessAIError> {
+        let sig: Signature = signature.parse()?;
+        let size = Table::size(&Table::layout(sig)).filter(|&n| n <= MAX_TABLE_POSITIONS);
+        if size.is_none() {
+            return Err(ChessAIError::BadTablebase(format!("{sig} is too large to generate")));
+        }
+        self.generate_sig(sig);
+        Ok(())
+    }
+
+    fn generate_sig(&mut self, sig: Signature) {
+        if self.contains(sig) {
+            return;
+        }
+        for sub in sig.captures() {
+            self.generate_sig(sub);
+        }
+        let table = Table::generate(sig, self);
+        self.insert(sig, table);
+    }
+
+    fn contains(&self, sig: Signature) -> bool {
+        self.tables.contains_key(&sig) || self.tables.contains_key(&sig.flipped())
+    }
+
+    fn insert(&mut self, sig: Signature, table: Table) {
+        self.max_pieces = self.max_pieces.max(sig.pieces() as u32 + 2);
+        self.tables.insert(sig, table);
+    }
+
+    /// Signatures of the loaded tables, e.g. `KRKAA`.
+    pub fn signatures(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.tables.keys().map(Signature::to_string).collect();
+        names.sort();
+        names
+    }
+
+    pub fn len(&self) -> usize { self.tables.len() }
+
+    pub fn is_empty(&self) -> bool { self.tables.is_empty() }
+
+    /// Most pieces, kings included, of any loaded table.
+    pub(crate) fn max_pieces(&self) -> u32 { self.max_pieces }
+
+    /// Value of `pos` for the side to move, if a table covers its material.
+    pub fn probe(&self, pos: &Position) -> Option<TbProbe> {
+        let sig = Signature::of(pos);
+        if let Some(table) = self.tables.get(&sig) {
+            return table.probe(pos, false);
+        }
+        self.tables.get(&sig.flipped())?.probe(pos, true)
+    }
+
+    /// The best move in `pos` by the tables — the fastest mate when winning, the slowest
+    /// when losing, any drawing move otherwise — with the probe of `pos` itself. `None`
+    /// if `pos` is not covered or has no legal move.
+    pub fn best_move(&self, pos: &Position) -> Option<(Move, TbProbe)> {
+        let root = self.probe(pos)?;
+        let mut legal = MoveList::new();
+        generate_legal(pos, &mut legal);
+        let mut scratch = pos.clone();
+        let mut best: Option<(Move, i32)> = None;
+        for &mv in legal.iter() {
+            let undo = scratch.make_move(mv);
+            let reply = self.probe(&scratch);
+            scratch.undo_move(mv, undo);
+            // Rank replies from the mover's side: opponent lost soonest, then draws, then
+            // opponent winning latest.
+            let rank = match reply? {
+                TbProbe { wdl: Wdl::Loss, dtm } => i32::MAX - dtm as i32,
+                TbProbe { wdl: Wdl::Draw, .. } => 0,
+                TbProbe { wdl: Wdl::Win, dtm } => i32::MIN + dtm as i32,
+            };
+            if best.is_none_or(|(_, r)| rank > r) {
+                best = Some((mv, rank));
+            }
+        }
+        best.map(|(mv, _)| (mv, root))
+    }
+
+    /// Write every table to `dir` as `<SIGNATURE>.xtb`, creating the directory if needed.
+    pub fn save_dir(&self, dir: impl AsRef<Path>) -> Result<(), ChessAIError> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        for (sig, table) in &self.tables {
+            let file = File::create(dir.join(format!("{sig}.{TABLE_FILE_EXT}")))?;
+            table.write_to(*sig, BufWriter::new(file))?;
+        }
+        Ok(())
+    }
+
+    /// Load every `.xtb` table in `dir`. Tables for smaller endings are not required; a
+    /// position whose capture leads outside the loaded set is simply not improved on.
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> Result<(), ChessAIError> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == TABLE_FILE_EXT) {
+                let (sig, table) = Table::read_from(BufReader::new(File::open(&path)?))?;
+                self.insert(sig, table);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn krk() -> Tablebases {
+        let mut tb = Tablebases::new();
+        tb.generate("KRK").unwrap();
+        tb
+    }
+
+    #[test]
+    fn signatures_parse_and_print_canonically() {
+        assert_eq!("KRKAA".parse::<Signature>().unwrap().to_string(), "KRKAA");
+        assert_eq!("KPNKBB".parse::<Signature>().unwrap().to_string(), "KNPKBB");
+        for bad in ["KRRRK", "RK", "KR", "KRKAK", "KXK"] {
+            assert!(bad.parse::<Signature>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn generation_builds_capture_tables() {
+        let tb = krk();
+        assert_eq!(tb.signatures(), ["KK", "KRK"]);
+        assert_eq!(tb.max_pieces(), 3);
+    }
+
+    #[test]
+    fn rook_beats_bare_king() {
+        let tb = krk();
+        let table = &tb.tables[&"KRK".parse().unwrap()];
+        for idx in 0..table.values.len() {
+            if let Some(pos) = table.decode(idx) {
+                let probe = tb.probe(&pos).unwrap();
+                if pos.side_to_move() == Color::Red {
+                    assert_eq!(probe.wdl, Wdl::Win, "{}", pos.to_fen());
+                } else {
+                    assert_ne!(probe.wdl, Wdl::Win, "{}", pos.to_fen());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn best_moves_mate_in_dtm_plies() {
+        let tb = krk();
+        let mut pos = Position::from_fen("4k4/9/9/9/9/9/9/9/9/3K4R w").unwrap();
+        let (_, root) = tb.best_move(&pos).unwrap();
+        assert_eq!(root.wdl, Wdl::Win);
+        let mut plies = 0;
+        while let Some((mv, probe)) = tb.best_move(&pos) {
+            assert_eq!(probe.dtm, root.dtm - plies);
+            pos.make_move(mv);
+            plies += 1;
+        }
+        assert_eq!(plies, root.dtm);
+        assert_eq!(pos.side_to_move(), Color::Black);
+    }
+
+    #[test]
+    fn mirrored_and_colour_flipped_positions_agree() {
+        let tb = krk();
+        let probe = |fen| tb.probe(&Position::from_fen(fen).unwrap()).unwrap();
+        let base = probe("5k3/9/9/9/9/9/9/9/2R6/3K5 w");
+        assert_eq!(probe("3k5/9/9/9/9/9/9/9/6R2/5K3 w"), base);
+        assert_eq!(probe("3k5/2r6/9/9/9/9/9/9/9/5K3 b"), base);
+    }
+
+    #[test]
+    fn tables_roundtrip_through_files() {
+        let tb = krk();
+        let dir = std::env::temp_dir().join(format!("chessai-tb-{}", std::process::id()));
+        tb.save_dir(&dir).unwrap();
+        let mut loaded = Tablebases::new();
+        loaded.load_dir(&dir).unwrap();
+        assert_eq!(loaded.signatures(), tb.signatures());
+        let sig = "KRK".parse().unwrap();
+        assert_eq!(loaded.tables[&sig].values, tb.tables[&sig].values);
+
+        let path = dir.join("KRK.xtb");
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[40] ^= 1;
+        assert!(
+            matches!(Table::read_from(bytes.as_slice()), Err(ChessAIError::BadTablebase(m)) if m.contains("checksum"))
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,10 +1,19 @@
+use std::alloc::Layout;
+use std::io::Read;
+use std::io::Write;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::ptr::NonNull;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 
+use crate::error::ChessAIError;
 use crate::eval::BAN_VALUE;
 use crate::eval::MATE_VALUE;
 use crate::eval::WIN_VALUE;
 use crate::mv::Move;
+use crate::util::Fnv64;
+use crate::zobrist::ZOBRIST;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -113,16 +122,112 @@ pub(crate) struct Cluster {
     entries: [TtEntry; 4],
 }
 
+/// Transparent huge-page size on x86-64 and most aarch64 Linux kernels.
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Entries sampled by [`TranspositionTable::hashfull`].
+const HASHFULL_SAMPLE: usize = 1000;
+
+/// Zero-initialised cluster storage. A plain boxed slice cannot carry the 2 MiB alignment
+/// huge pages want, so the buffer owns its allocation and layout directly.
+struct ClusterBuf {
+    ptr: NonNull<Cluster>,
+    len: usize,
+    layout: Layout,
+}
+
+// SAFETY: the buffer uniquely owns its allocation, and `Cluster` is made of atomics.
+unsafe impl Send for ClusterBuf {}
+unsafe impl Sync for ClusterBuf {}
+
+impl ClusterBuf {
+    /// Allocate `len` zeroed clusters. With `large_pages`, tables of at least one huge page
+    /// are aligned to it and, on Linux, advised for transparent huge pages before first
+    /// touch; elsewhere, or if the kernel declines, normal pages are used.
+    fn zeroed(len: usize, large_pages: bool) -> Self {
+        let bytes = len.checked_mul(std::mem::size_of::<Cluster>()).expect("table size overflows");
+        assert!(bytes > 0, "table must hold at least one cluster");
+        let huge = large_pages && bytes >= HUGE_PAGE_SIZE;
+        let align = if huge { HUGE_PAGE_SIZE } else { std::mem::align_of::<Cluster>() };
+        let layout = Layout::from_size_align(bytes, align).expect("table size overflows");
+        // SAFETY: `layout` has a nonzero size. An all-zero `Cluster` is a valid value
+        // (every field is an `AtomicU64`), and the huge path zeroes before handing out.
+        let raw = unsafe {
+            if huge {
+                let raw = std::alloc::alloc(layout);
+                if !raw.is_null() {
+                    advise_huge_pages(raw, bytes);
+                    raw.write_bytes(0, bytes);
+                }
+                raw
+            } else {
+                std::alloc::alloc_zeroed(layout)
+            }
+        };
+        let Some(ptr) = NonNull::new(raw.cast::<Cluster>()) else { std::alloc::handle_alloc_error(layout) };
+        ClusterBuf { ptr, len, layout }
+    }
+}
+
+impl Deref for ClusterBuf {
+    type Target = [Cluster];
+
+    // SAFETY: `ptr` points to `len` initialised clusters for the buffer's lifetime.
+    fn deref(&self) -> &[Cluster] { unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) } }
+}
+
+impl DerefMut for ClusterBuf {
+    // SAFETY: as for `deref`; `&mut self` guarantees exclusive access.
+    fn deref_mut(&mut self) -> &mut [Cluster] { unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) } }
+}
+
+impl Drop for ClusterBuf {
+    // SAFETY: allocated in `zeroed` with exactly this layout.
+    fn drop(&mut self) { unsafe { std::alloc::dealloc(self.ptr.as_ptr().cast(), self.layout) } }
+}
+
+#[cfg(target_os = "linux")]
+fn advise_huge_pages(ptr: *mut u8, len: usize) {
+    const MADV_HUGEPAGE: i32 = 14;
+    unsafe extern "C" {
+        fn madvise(addr: *mut std::ffi::c_void, len: usize, advice: i32) -> i32;
+    }
+    // SAFETY: `ptr..ptr + len` is a live allocation aligned to the huge-page size. Failure
+    // (no THP support, or THP disabled) is harmless and deliberately ignored.
+    let _ = unsafe { madvise(ptr.cast(), len, MADV_HUGEPAGE) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn advise_huge_pages(_ptr: *mut u8, _len: usize) {}
+
+/// Leading bytes of a saved table.
+const HASH_FILE_MAGIC: [u8; 8] = *b"CHESSAIT";
+/// Version of the file layout written by [`TranspositionTable::write_to`].
+const HASH_FILE_VERSION: u32 = 1;
+/// Identifies the entry packing: entries per cluster in the high half, a revision of the
+/// `pack` layout in the low half. Bump the revision whenever `pack` changes.
+const PACKING_ID: u32 = (4 << 16) | 1;
+/// Largest cluster count accepted from a file (16 GiB of table).
+const MAX_FILE_CLUSTERS: u64 = 1 << 28;
+/// Bytes before the first entry: magic, version, packing id, fingerprint, clusters, age.
+const HASH_FILE_HEADER: u64 = 8 + 4 + 4 + 8 + 8 + 8;
+/// Bytes per cluster in the file: four `(key ^ data, data)` pairs.
+const HASH_FILE_CLUSTER: u64 = 4 * 16;
+
 pub(crate) struct TranspositionTable {
-    clusters: Box<[Cluster]>,
+    clusters: ClusterBuf,
     mask: usize,
     age: AtomicU64, // 6-bit value; AtomicU64 chosen for simple fetch_add ergonomics
+    large_pages: bool,
 }
 
 impl TranspositionTable {
     /// Build a table sized to at most `size_bytes`, rounded down to a power-of-two number
     /// of 64-byte clusters.
-    pub(crate) fn new(size_bytes: usize) -> Self {
+    pub(crate) fn new(size_bytes: usize) -> Self { Self::allocate(size_bytes, false) }
+
+    /// Like [`new`](Self::new), optionally backed by huge pages (see `ClusterBuf::zeroed`).
+    pub(crate) fn allocate(size_bytes: usize, large_pages: bool) -> Self {
         let min_clusters = 1024usize;
         let cluster_size = std::mem::size_of::<Cluster>();
         let wanted = (size_bytes / cluster_size).max(min_clusters);
@@ -131,12 +236,116 @@ impl TranspositionTable {
         if clusters > wanted {
             clusters /= 2;
         }
-        let clusters = clusters.max(min_clusters);
-        let mut v = Vec::with_capacity(clusters);
-        for _ in 0..clusters {
-            v.push(Cluster::default());
+        Self::with_clusters(clusters.max(min_clusters), large_pages)
+    }
+
+    /// Build an empty table of exactly `clusters` clusters, which must be a power of two.
+    fn with_clusters(clusters: usize, large_pages: bool) -> Self {
+        debug_assert!(clusters.is_power_of_two());
+        TranspositionTable {
+            clusters: ClusterBuf::zeroed(clusters, large_pages),
+            mask: clusters - 1,
+            age: AtomicU64::new(0),
+            large_pages,
+        }
+    }
+
+    /// Serialise every cluster and the age counter. Layout, all integers little-endian:
+    /// magic, version `u32`, packing id `u32`, Zobrist fingerprint `u64`, cluster count
+    /// `u64`, age `u64`, then each entry as `(key ^ data, data)`, and finally an FNV-1a
+    /// checksum of everything before it.
+    ///
+    /// Entries are read with relaxed loads, so save only while no search is running.
+    pub(crate) fn write_to<W: Write>(&self, mut w: W) -> Result<(), ChessAIError> {
+        let mut sum = Fnv64::new();
+        let mut put = |bytes: &[u8]| -> Result<(), ChessAIError> {
+            sum.update(bytes);
+            w.write_all(bytes)?;
+            Ok(())
+        };
+        put(&HASH_FILE_MAGIC)?;
+        put(&HASH_FILE_VERSION.to_le_bytes())?;
+        put(&PACKING_ID.to_le_bytes())?;
+        put(&ZOBRIST.fingerprint().to_le_bytes())?;
+        put(&(self.clusters.len() as u64).to_le_bytes())?;
+        put(&self.age.load(Ordering::Relaxed).to_le_bytes())?;
+        for cluster in self.clusters.iter() {
+            for entry in &cluster.entries {
+                let (data, key_xor) = entry.load_raw();
+                put(&key_xor.to_le_bytes())?;
+                put(&data.to_le_bytes())?;
+            }
+        }
+        let checksum = sum.finish();
+        w.write_all(&checksum.to_le_bytes())?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Read a table written by [`write_to`](Self::write_to) from a stream of `len` bytes.
+    /// The result has the file's size, not the caller's configured one. Files from another
+    /// format version, entry packing or Zobrist key layout are rejected, as is any file
+    /// whose checksum does not match. The table is only allocated once `len` is known to
+    /// hold every cluster the header announces.
+    pub(crate) fn read_from<R: Read>(mut r: R, len: u64, large_pages: bool) -> Result<Self, ChessAIError> {
+        let mut sum = Fnv64::new();
+        let mut take = |buf: &mut [u8]| -> Result<(), ChessAIError> {
+            r.read_exact(buf).map_err(|_| ChessAIError::BadHashFile("truncated".into()))?;
+            sum.update(buf);
+            Ok(())
+        };
+        let mut magic = [0u8; 8];
+        take(&mut magic)?;
+        if magic != HASH_FILE_MAGIC {
+            return Err(ChessAIError::BadHashFile("not a hash file".into()));
+        }
+        let mut word = [0u8; 4];
+        take(&mut word)?;
+        let version = u32::from_le_bytes(word);
+        if version != HASH_FILE_VERSION {
+            return Err(ChessAIError::BadHashFile(format!("unsupported version {version}")));
+        }
+        take(&mut word)?;
+        if u32::from_le_bytes(word) != PACKING_ID {
+            return Err(ChessAIError::BadHashFile("entry packing differs".into()));
+        }
+        let mut quad = [0u8; 8];
+        take(&mut quad)?;
+        if u64::from_le_bytes(quad) != ZOBRIST.fingerprint() {
+            return Err(ChessAIError::BadHashFile("Zobrist key layout differs".into()));
+        }
+        take(&mut quad)?;
+        let clusters = u64::from_le_bytes(quad);
+        if !clusters.is_power_of_two() || clusters > MAX_FILE_CLUSTERS {
+            return Err(ChessAIError::BadHashFile(format!("invalid cluster count {clusters}")));
+        }
+        let expected = HASH_FILE_HEADER + clusters * HASH_FILE_CLUSTER + 8;
+        if len < expected {
+            return Err(ChessAIError::BadHashFile("truncated".into()));
+        }
+        if len > expected {
+            return Err(ChessAIError::BadHashFile(format!("{} trailing bytes", len - expected)));
         }
-        TranspositionTable { clusters: v.into_boxed_slice(), mask: clusters - 1, age: AtomicU64::new(0) }
+        take(&mut quad)?;
+        let age = u64::from_le_bytes(quad);
+
+        let tt = Self::with_clusters(clusters as usize, large_pages);
+        tt.age.store(age, Ordering::Relaxed);
+        let mut pair = [0u8; 16];
+        for cluster in tt.clusters.iter() {
+            for entry in &cluster.entries {
+                take(&mut pair)?;
+                let (key_xor, data) = pair.split_at(8);
+                entry.key_xor_data.store(u64::from_le_bytes(key_xor.try_into().unwrap()), Ordering::Relaxed);
+                entry.data.store(u64::from_le_bytes(data.try_into().unwrap()), Ordering::Relaxed);
+            }
+        }
+        let expected = sum.finish();
+        r.read_exact(&mut quad).map_err(|_| ChessAIError::BadHashFile("truncated".into()))?;
+        if u64::from_le_bytes(quad) != expected {
+            return Err(ChessAIError::BadHashFile("checksum mismatch".into()));
+        }
+        Ok(tt)
     }
 
     pub(crate) fn clear(&mut self) {
@@ -155,6 +364,27 @@ impl TranspositionTable {
 
     pub(crate) fn size_bytes(&self) -> usize { self.clusters.len() * std::mem::size_of::<Cluster>() }
 
+    /// Whether huge pages were requested for this table.
+    pub(crate) fn large_pages(&self) -> bool { self.large_pages }
+
+    /// Occupancy in per mille, estimated from the first [`HASHFULL_SAMPLE`] entries: only
+    /// entries written under the current age count, so stale results from earlier
+    /// searches do not make a fresh table look full.
+    pub(crate) fn hashfull(&self) -> u16 {
+        let age = self.current_age();
+        let used = self
+            .clusters
+            .iter()
+            .flat_map(|cluster| &cluster.entries)
+            .take(HASHFULL_SAMPLE)
+            .filter(|entry| {
+                let (data, key_xor) = entry.load_raw();
+                (data != 0 || key_xor != 0) && unpack_age(data) == age
+            })
+            .count();
+        (used * 1000 / HASHFULL_SAMPLE) as u16
+    }
+
     #[inline]
     fn cluster(&self, key: u64) -> &Cluster { &self.clusters[(key as usize) & self.mask] }
 
@@ -289,6 +519,69 @@ mod tests {
         assert_eq!(tt.probe(key).unwrap().value, 20);
     }
 
+    #[test]
+    fn file_roundtrip_keeps_entries_and_age() {
+        let tt = TranspositionTable::new(1 << 16);
+        tt.bump_age();
+        tt.bump_age();
+        tt.store(0x1234_5678_9ABC_DEF0, Move::from_raw(0x0102), -77, 9, Bound::Beta, 0);
+        let mut bytes = Vec::new();
+        tt.write_to(&mut bytes).unwrap();
+
+        let loaded = TranspositionTable::read_from(bytes.as_slice(), bytes.len() as u64, false).unwrap();
+        assert_eq!(loaded.size_bytes(), tt.size_bytes());
+        assert_eq!(loaded.current_age(), 2);
+        let hit = loaded.probe(0x1234_5678_9ABC_DEF0).unwrap();
+        assert_eq!((hit.mv, hit.value, hit.depth, hit.bound), (Move::from_raw(0x0102), -77, 9, Bound::Beta));
+    }
+
+    #[test]
+    fn corrupt_or_foreign_files_are_rejected() {
+        let tt = TranspositionTable::new(1 << 16);
+        tt.store(42, Move::NULL, 1, 1, Bound::Exact, 0);
+        let mut bytes = Vec::new();
+        tt.write_to(&mut bytes).unwrap();
+        let reject = |patch: &dyn Fn(&mut Vec<u8>)| {
+            let mut b = bytes.clone();
+            patch(&mut b);
+            TranspositionTable::read_from(b.as_slice(), b.len() as u64, false).err().expect("must be rejected")
+        };
+
+        assert!(matches!(reject(&|b| b[100] ^= 1), ChessAIError::BadHashFile(m) if m.contains("checksum")));
+        assert!(matches!(reject(&|b| b[12] ^= 1), ChessAIError::BadHashFile(m) if m.contains("packing")));
+        assert!(matches!(reject(&|b| b[16] ^= 1), ChessAIError::BadHashFile(m) if m.contains("Zobrist")));
+        assert!(matches!(reject(&|b| b[24] = 3), ChessAIError::BadHashFile(m) if m.contains("cluster")));
+        assert!(matches!(reject(&|b| b.truncate(b.len() - 1)), ChessAIError::BadHashFile(m) if m == "truncated"));
+        assert!(matches!(reject(&|b| b.push(0)), ChessAIError::BadHashFile(m) if m.contains("trailing")));
+        // A header announcing a huge table is refused before anything is allocated.
+        let huge = |b: &mut Vec<u8>| b[24..32].copy_from_slice(&MAX_FILE_CLUSTERS.to_le_bytes());
+        assert!(matches!(reject(&huge), ChessAIError::BadHashFile(m) if m == "truncated"));
+    }
+
+    #[test]
+    fn hashfull_counts_current_age_only() {
+        let tt = TranspositionTable::new(1 << 16);
+        assert_eq!(tt.hashfull(), 0);
+        // Consecutive keys land in consecutive clusters, so these fill the sampled prefix.
+        for key in 0..500u64 {
+            tt.store(key, Move::NULL, 1, 1, Bound::Exact, 0);
+        }
+        assert_eq!(tt.hashfull(), 250);
+        tt.bump_age();
+        assert_eq!(tt.hashfull(), 0);
+    }
+
+    #[test]
+    fn large_page_table_is_aligned_and_usable() {
+        let mut tt = TranspositionTable::allocate(HUGE_PAGE_SIZE, true);
+        assert_eq!(tt.size_bytes(), HUGE_PAGE_SIZE);
+        assert_eq!(tt.clusters.as_ptr() as usize % HUGE_PAGE_SIZE, 0);
+        tt.store(7, Move::NULL, 3, 2, Bound::Alpha, 0);
+        assert_eq!(tt.probe(7).unwrap().value, 3);
+        tt.clear();
+        assert!(tt.probe(7).is_none());
+    }
+
     #[test]
     fn mate_score_roundtrip() {
         let score = MATE_VALUE - 10;
@@ -0,0 +1,384 @@
+//! Texel-style evaluation tuning.
+//!
+//! Every labelled position is first resolved to a quiet leaf with the engine's own
+//! `Search::quiesce`. The leaf is then reduced to a sparse feature vector (one positional
+//! and one material entry per piece, plus the side-to-move bonus) so that the evaluation
+//! becomes a linear function of the parameter vector. Parameters are fitted with Adam-style gradient
+//! descent on the mean squared error between the game result and a logistic mapping of the
+//! evaluation, then exported as Rust source that can be pasted over the tables in `eval.rs`,
+//! or as an [`EvalParams`] file the engine loads at runtime.
+//!
+//! ```no_run
+//! use chessai::{TexelTuner, TuningPosition};
+//!
+//! let data = std::fs::read_to_string("positions.txt").unwrap();
+//! let positions: Vec<TuningPosition> = data.lines().filter_map(|l| l.parse().ok()).collect();
+//! let mut tuner = TexelTuner::new(&positions).epochs(200);
+//! tuner.fit_scaling();
+//! tuner.run(|epoch, err| println!("epoch {epoch}: error {err:.6}"));
+//! println!("{}", tuner.to_rust_source());
+//! ```
+
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crate::color::Color;
+use crate::error::ChessAIError;
+use crate::eval::ADVANCED_VALUE;
+use crate::eval::EvalParams;
+use crate::eval::PIECE_NAMES;
+use crate::eval::PIECE_VALUES;
+use crate::eval::PST;
+use crate::eval::WIN_VALUE;
+use crate::piece::PieceType;
+use crate::position::Position;
+use crate::search::Search;
+use crate::square::Square;
+use crate::tt::TranspositionTable;
+
+/// Number of positional PST entries (`7 × 90`); the piece values follow, then the
+/// side-to-move bonus.
+const PST_PARAMS: usize = PieceType::COUNT * Square::COUNT;
+const MATERIAL_INDEX: usize = PST_PARAMS;
+const TEMPO_INDEX: usize = MATERIAL_INDEX + PieceType::COUNT;
+const PARAM_COUNT: usize = TEMPO_INDEX + 1;
+
+/// A position labelled with the final result of the game it was taken from.
+///
+/// Text form: `<fen> | <result>`. Any `|`-separated fields between the FEN and the result
+/// are ignored, so annotated dumps (score, best move, …) can be fed in unchanged. The
+/// result is from red's point of view and accepts `1-0` / `0-1` / `1/2-1/2`, or a number
+/// in `0.0..=1.0`, optionally wrapped in brackets (`[0.5]`).
+#[derive(Clone, Debug)]
+pub struct TuningPosition {
+    pub(crate) position: Position,
+    pub(crate) result: f64,
+}
+
+impl TuningPosition {
+    pub fn new(position: Position, red_result: f64) -> Self {
+        TuningPosition { position, result: red_result.clamp(0.0, 1.0) }
+    }
+
+    pub fn position(&self) -> &Position { &self.position }
+
+    /// Game result from red's perspective: `1.0` red won, `0.5` draw, `0.0` black won.
+    pub fn result(&self) -> f64 { self.result }
+}
+
+impl FromStr for TuningPosition {
+    type Err = ChessAIError;
+
+    fn from_str(line: &str) -> Result<TuningPosition, ChessAIError> {
+        let bad = || ChessAIError::BadTuningRecord(line.to_string());
+        let (fen, rest) = line.split_once('|').ok_or_else(bad)?;
+        let field = rest.rsplit('|').next().unwrap_or(rest).trim();
+        let field = field.trim_start_matches('[').trim_end_matches(']').trim();
+        let result = match field {
+            "1-0" => 1.0,
+            "0-1" => 0.0,
+            "1/2-1/2" | "1/2" | "=" => 0.5,
+            other => match other.parse::<f64>() {
+                Ok(v) if (0.0..=1.0).contains(&v) => v,
+                _ => return Err(bad()),
+            },
+        };
+        Ok(TuningPosition { position: Position::from_fen(fen)?, result })
+    }
+}
+
+/// Sparse linear view of one quiet leaf: red-perspective evaluation = Σ coeff · param.
+struct Sample {
+    features: Vec<(u16, i8)>,
+    result: f64,
+}
+
+/// Fits the piece-square tables, the piece values and the side-to-move bonus to labelled
+/// positions.
+///
+/// Every PST entry is split into the piece's value ([`EvalParams::piece_values`]) and a
+/// positional remainder, and both are tuned: moving a piece value shifts its whole table,
+/// so material is learnt from every position the piece appears in. The tuned tables are
+/// exported with the tuned value folded back in, so the evaluation they give is the one
+/// that was fitted.
+pub struct TexelTuner {
+    samples: Vec<Sample>,
+    params: Vec<f64>,
+    k: f64,
+    learning_rate: f64,
+    epochs: u32,
+}
+
+impl TexelTuner {
+    /// Resolve every position to its quiescence leaf and seed the parameter vector with the
+    /// compile-time tables. Positions whose quiescence search ends in a mate are dropped —
+    /// their evaluation is not a function of the tables.
+    pub fn new(positions: &[TuningPosition]) -> Self {
+        let tt = Arc::new(TranspositionTable::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut samples = Vec::with_capacity(positions.len());
+        for tp in positions {
+            let mut pos = tp.position.clone();
+            let (score, line) = {
+                let mut search = Search::new(&mut pos, Arc::clone(&tt), Arc::clone(&stop));
+                search.quiesce_pv()
+            };
+            if score.abs() > WIN_VALUE {
+                continue;
+            }
+            for mv in &line {
+                pos.make_move(*mv);
+            }
+            samples.push(Sample { features: leaf_features(&pos), result: tp.result });
+        }
+
+        let mut params = Vec::with_capacity(PARAM_COUNT);
+        for (table, value) in PST.iter().zip(PIECE_VALUES) {
+            params.extend(table.iter().map(|&v| (v as i32 - value) as f64));
+        }
+        params.extend(PIECE_VALUES.iter().map(|&v| v as f64));
+        params.push(ADVANCED_VALUE as f64);
+
+        TexelTuner { samples, params, k: 1.0, learning_rate: 1.0, epochs: 100 }
+    }
+
+    /// Number of usable samples (after dropping mated leaves).
+    pub fn len(&self) -> usize { self.samples.len() }
+
+    pub fn is_empty(&self) -> bool { self.samples.is_empty() }
+
+    /// Step size of the gradient descent, in evaluation units.
+    #[must_use]
+    pub fn learning_rate(mut self, lr: f64) -> Self {
+        self.learning_rate = lr;
+        self
+    }
+
+    #[must_use]
+    pub fn epochs(mut self, n: u32) -> Self {
+        self.epochs = n;
+        self
+    }
+
+    /// Scaling constant of the logistic `1 / (1 + 10^(-k·eval/400))`.
+    pub fn scaling(&self) -> f64 { self.k }
+
+    /// Fit the logistic scaling constant to the current parameters by golden-section
+    /// search, so that gradient descent only has to move the tables themselves.
+    pub fn fit_scaling(&mut self) -> f64 {
+        let (mut lo, mut hi) = (0.01f64, 10.0f64);
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        for _ in 0..60 {
+            let a = hi - ratio * (hi - lo);
+            let b = lo + ratio * (hi - lo);
+            if self.error_with(a) < self.error_with(b) {
+                hi = b;
+            } else {
+                lo = a;
+            }
+        }
+        self.k = (lo + hi) / 2.0;
+        self.k
+    }
+
+    /// Mean squared error of the current parameters.
+    pub fn error(&self) -> f64 { self.error_with(self.k) }
+
+    fn error_with(&self, k: f64) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self
+            .samples
+            .iter()
+            .map(|s| {
+                let d = s.result - sigmoid(k, self.eval(s));
+                d * d
+            })
+            .sum();
+        total / self.samples.len() as f64
+    }
+
+    #[inline]
+    fn eval(&self, s: &Sample) -> f64 { s.features.iter().map(|&(i, c)| self.params[i as usize] * c as f64).sum() }
+
+    /// Run the configured number of epochs. `progress` receives the epoch number and the
+    /// error after it. Returns the final error.
+    pub fn run(&mut self, mut progress: impl FnMut(u32, f64)) -> f64 {
+        const BETA1: f64 = 0.9;
+        const BETA2: f64 = 0.999;
+        const EPS: f64 = 1e-8;
+
+        let n = self.samples.len().max(1) as f64;
+        let scale = self.k * std::f64::consts::LN_10 / 400.0;
+        let mut m = vec![0.0; PARAM_COUNT];
+        let mut v = vec![0.0; PARAM_COUNT];
+        let mut grad = vec![0.0; PARAM_COUNT];
+
+        for epoch in 1..=self.epochs {
+            grad.iter_mut().for_each(|g| *g = 0.0);
+            for s in &self.samples {
+                let p = sigmoid(self.k, self.eval(s));
+                // d/dθ (r - σ)² = -2 (r - σ) σ (1 - σ) · scale · x
+                let common = -2.0 * (s.result - p) * p * (1.0 - p) * scale / n;
+                for &(i, c) in &s.features {
+                    grad[i as usize] += common * c as f64;
+                }
+            }
+            let t = epoch as i32;
+            for i in 0..PARAM_COUNT {
+                if grad[i] == 0.0 {
+                    continue;
+                }
+                m[i] = BETA1 * m[i] + (1.0 - BETA1) * grad[i];
+                v[i] = BETA2 * v[i] + (1.0 - BETA2) * grad[i] * grad[i];
+                let m_hat = m[i] / (1.0 - BETA1.powi(t));
+                let v_hat = v[i] / (1.0 - BETA2.powi(t));
+                self.params[i] -= self.learning_rate * m_hat / (v_hat.sqrt() + EPS);
+            }
+            progress(epoch, self.error());
+        }
+        self.error()
+    }
+
+    /// Tuned piece-square tables (piece value plus positional term), rounded to the
+    /// integer representation used by `eval.rs`.
+    pub fn pst(&self) -> [[i16; 90]; 7] {
+        let mut out = [[0i16; 90]; 7];
+        for (kind, table) in out.iter_mut().enumerate() {
+            for (sq, slot) in table.iter_mut().enumerate() {
+                *slot = (self.params[kind * Square::COUNT + sq] + self.params[MATERIAL_INDEX + kind]).round() as i16;
+            }
+        }
+        out
+    }
+
+    /// Tuned piece values (`PIECE_VALUES`), by `PieceType`.
+    pub fn piece_values(&self) -> [i32; PieceType::COUNT] {
+        std::array::from_fn(|kind| self.params[MATERIAL_INDEX + kind].round() as i32)
+    }
+
+    /// Tuned side-to-move bonus (`ADVANCED_VALUE`).
+    pub fn advanced_value(&self) -> i32 { self.params[TEMPO_INDEX].round() as i32 }
+
+    /// Tuned values as a runtime parameter set (other constants keep their defaults). Its
+    /// text form can be saved and loaded back via `EngineBuilder::eval_params`.
+    pub fn eval_params(&self) -> EvalParams {
+        EvalParams {
+            pst: self.pst(),
+            piece_values: self.piece_values(),
+            advanced_value: self.advanced_value(),
+            ..EvalParams::default()
+        }
+    }
+
+    /// Render the tuned values as Rust source in the layout of `eval.rs`.
+    pub fn to_rust_source(&self) -> String {
+        let pst = self.pst();
+        let mut out = String::new();
+        let _ = writeln!(out, "pub const ADVANCED_VALUE: i32 = {};", self.advanced_value());
+        let values: Vec<String> = self.piece_values().iter().map(|v| v.to_string()).collect();
+        let _ = writeln!(out, "pub const PIECE_VALUES: [i32; PieceType::COUNT] = [{}];", values.join(", "));
+        let _ = writeln!(out);
+        let _ = writeln!(out, "pub const PST: [[i16; 90]; 7] = [");
+        for (kind, table) in pst.iter().enumerate() {
+            let _ = writeln!(out, "    // {}", PIECE_NAMES[kind]);
+            let _ = writeln!(out, "    [");
+            for (rank, row) in table.chunks(9).enumerate() {
+                let cells: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+                let _ = writeln!(out, "        {}, // rank {rank}", cells.join(", "));
+            }
+            let _ = writeln!(out, "    ],");
+        }
+        let _ = writeln!(out, "];");
+        out
+    }
+}
+
+#[inline]
+fn sigmoid(k: f64, eval: f64) -> f64 { 1.0 / (1.0 + 10f64.powf(-k * eval / 400.0)) }
+
+/// Feature vector of a quiet leaf, mirroring `evaluate`: each piece contributes its
+/// positional entry and its piece value (black pieces with a negative sign, via the
+/// rank-flipped index) and the `ADVANCED_VALUE` bonus is added to red's score.
+fn leaf_features(pos: &Position) -> Vec<(u16, i8)> {
+    let mut features = Vec::with_capacity(65);
+    for raw in 0..Square::COUNT as u8 {
+        let sq = Square::new_unchecked(raw);
+        let Some(piece) = pos.piece_at(sq) else {
+            continue;
+        };
+        let (idx, coeff) = match piece.color() {
+            Color::Red => (sq.raw(), 1),
+            Color::Black => (sq.flip_rank().raw(), -1),
+        };
+        features.push(((piece.kind().index() * Square::COUNT + idx as usize) as u16, coeff));
+        features.push(((MATERIAL_INDEX + piece.kind().index()) as u16, coeff));
+    }
+    features.push((TEMPO_INDEX as u16, 1));
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::evaluate;
+    use crate::fen::STARTING_FEN;
+
+    #[test]
+    fn parses_result_notations() {
+        let red: TuningPosition = format!("{STARTING_FEN} | 1-0").parse().unwrap();
+        assert_eq!(red.result(), 1.0);
+        let draw: TuningPosition = format!("{STARTING_FEN} | 12 | h2e2 | [0.5]").parse().unwrap();
+        assert_eq!(draw.result(), 0.5);
+        assert!(format!("{STARTING_FEN} | 2.0").parse::<TuningPosition>().is_err());
+        assert!(STARTING_FEN.parse::<TuningPosition>().is_err());
+    }
+
+    #[test]
+    fn linear_model_matches_static_eval() {
+        // With untouched parameters the feature model must reproduce `evaluate` exactly.
+        let pos = Position::from_fen("r1bakabr1/9/1cn1c1n2/p1p3p1p/4p4/2P6/P3P1P1P/2N1C1N2/9/R1BAKABR1 b").unwrap();
+        let tuner = TexelTuner::new(&[TuningPosition::new(pos.clone(), 0.5)]);
+        let sample = Sample { features: leaf_features(&pos), result: 0.5 };
+        let red_eval = match pos.side_to_move() {
+            Color::Red => evaluate(&pos),
+            Color::Black => -evaluate(&pos),
+        };
+        assert_eq!(tuner.eval(&sample) as i32, red_eval);
+    }
+
+    #[test]
+    fn tuning_reduces_error() {
+        let data = [
+            ("4k4/9/9/9/9/9/9/9/R8/3K5 w | 1-0", 1.0),
+            ("3k5/r8/9/9/9/9/9/9/9/4K4 b | 0-1", 0.0),
+            ("4k4/9/9/9/4p4/4P4/9/9/9/4K4 w | 1/2-1/2", 0.5),
+            ("3k5/9/9/9/9/9/9/4N4/9/4K4 w | 1-0", 1.0),
+        ];
+        let positions: Vec<TuningPosition> = data.iter().map(|(l, _)| l.parse().unwrap()).collect();
+        let mut tuner = TexelTuner::new(&positions).epochs(50).learning_rate(2.0);
+        assert_eq!(tuner.len(), data.len());
+        tuner.fit_scaling();
+        let before = tuner.error();
+        let after = tuner.run(|_, _| {});
+        assert!(after < before, "error went from {before} to {after}");
+        let values = tuner.piece_values();
+        assert_ne!(values[PieceType::Rook.index()], PIECE_VALUES[PieceType::Rook.index()]);
+        assert_eq!(tuner.eval_params().piece_values, values);
+    }
+
+    #[test]
+    fn rust_source_has_all_tables() {
+        let tuner = TexelTuner::new(&[]);
+        let src = tuner.to_rust_source();
+        assert!(src.contains(&format!("pub const ADVANCED_VALUE: i32 = {ADVANCED_VALUE};")));
+        assert!(src.contains(&format!("pub const PIECE_VALUES: [i32; PieceType::COUNT] = {PIECE_VALUES:?};")));
+        assert_eq!(tuner.piece_values(), PIECE_VALUES);
+        assert_eq!(src.matches("// rank").count(), 70);
+        assert_eq!(tuner.pst(), PST);
+        assert_eq!(tuner.eval_params(), EvalParams::default());
+    }
+}
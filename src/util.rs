@@ -63,6 +63,25 @@ impl SplitMix64 {
     pub(crate) fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
 }
 
+/// Streaming 64-bit FNV-1a. Used as the checksum of on-disk files; not cryptographic.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Fnv64(u64);
+
+impl Fnv64 {
+    pub(crate) const fn new() -> Self { Fnv64(0xcbf2_9ce4_8422_2325) }
+
+    #[inline]
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn finish(self) -> u64 { self.0 }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,6 +95,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fnv_matches_reference_vector() {
+        let mut h = Fnv64::new();
+        h.update(b"a");
+        assert_eq!(h.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+
     #[test]
     fn splitmix_distinct() {
         let mut r = SplitMix64::new(42);
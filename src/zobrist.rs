@@ -2,6 +2,7 @@ use std::sync::LazyLock;
 
 use crate::piece::Piece;
 use crate::square::Square;
+use crate::util::Fnv64;
 use crate::util::Rc4;
 use crate::util::SplitMix64;
 
@@ -17,6 +18,21 @@ pub struct ZobristTables {
 
 pub static ZOBRIST: LazyLock<ZobristTables> = LazyLock::new(build);
 
+impl ZobristTables {
+    /// Hash of every transposition-table key. Files holding TT entries record it so they
+    /// are never loaded under a different key layout.
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut h = Fnv64::new();
+        for row in &self.key_piece {
+            for key in row {
+                h.update(&key.to_le_bytes());
+            }
+        }
+        h.update(&self.key_stm.to_le_bytes());
+        h.finish()
+    }
+}
+
 /// Book file mailbox row stride (16 cells per row, 3-cell border on each side).
 const BOOK_MAILBOX_STRIDE: u8 = 16;
 /// Topmost playable rank in the book mailbox (the book stores the board rank-flipped).